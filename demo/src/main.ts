@@ -1,7 +1,7 @@
 import './style.css'
 import 'touch-pad/define'
 import { Scene, Transform } from '../../dist/index.js'
-import { renderFromBuffer } from '../../dist/wasm/penumbra-simd.js'
+import { SceneRenderer } from '../../dist/wasm/penumbra-simd.js'
 import {
   CameraController,
   type CartesianCoords,
@@ -107,6 +107,9 @@ function getPreviewDimensions() {
   }
 }
 
+// Keeps parsed objects between preview frames
+const previewRenderer = new SceneRenderer()
+
 // Preview render (main thread) - uses same buffer with different dimensions
 async function doPreviewRender(cameraPos: CartesianCoords) {
   updateCamera(cameraPos)
@@ -116,7 +119,7 @@ async function doPreviewRender(cameraPos: CartesianCoords) {
   const view = new Uint8Array(scene.getBuffer())
   const startTime = performance.now()
 
-  const imageData = renderFromBuffer(view, width, height, fov)
+  const imageData = previewRenderer.render(view, width, height, fov)
 
   const duration = performance.now() - startTime
 
@@ -156,6 +159,11 @@ renderWorker.onmessage = (e: MessageEvent) => {
     requestFullRender(cameraPos)
   }
 
+  if (e.data.type === 'progress') {
+    ctx.putImageData(e.data.imageData, 0, 0)
+    renderTime.textContent = 'Refining…'
+  }
+
   if (e.data.type === 'complete') {
     const { imageData, duration } = e.data
     ctx.putImageData(imageData, 0, 0)
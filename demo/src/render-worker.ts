@@ -1,11 +1,9 @@
-let renderFromBuffer: (
-  buffer: Uint8Array,
-  width: number,
-  height: number,
-  fov: number
-) => ImageData
+import type { PreviewRenderer } from '../../dist/wasm/penumbra-simd.js'
+
+let preview: PreviewRenderer
 let sceneBuffer: SharedArrayBuffer | null = null
 let wasmReady = false
+let currentRender = 0
 const pendingMessages: MessageEvent[] = []
 
 // Set up message handler immediately to queue messages
@@ -31,33 +29,48 @@ function handleMessage(e: MessageEvent) {
     }
 
     const { width, height, fov } = e.data
-    const view = new Uint8Array(sceneBuffer)
-    const startTime = performance.now()
+    const renderId = ++currentRender
+    preview.reset()
+    renderPass(renderId, width, height, fov, performance.now())
+  }
+}
 
-    try {
-      const wasmImageData = renderFromBuffer(view, width, height, fov)
-      const duration = performance.now() - startTime
+// Renders one interleaved pass, posting the partial image, then yields so a newer
+// render request can supersede this one before the next pass
+function renderPass(
+  renderId: number,
+  width: number,
+  height: number,
+  fov: number,
+  startTime: number
+) {
+  if (renderId !== currentRender || !sceneBuffer) return
+
+  try {
+    const view = new Uint8Array(sceneBuffer)
+    // Render straight into an array this worker owns, so it can be transferred
+    const pixels = new Uint8ClampedArray(width * height * 4)
+    preview.renderInto(view, width, height, fov, pixels)
+    const duration = performance.now() - startTime
+    const imageData = new ImageData(pixels, width, height)
 
-      // Copy ImageData since WASM memory can't be transferred
-      const imageData = new ImageData(
-        new Uint8ClampedArray(wasmImageData.data),
-        wasmImageData.width,
-        wasmImageData.height
-      )
+    const type = preview.isComplete ? 'complete' : 'progress'
+    self.postMessage({ type, imageData, duration }, {
+      transfer: [imageData.data.buffer],
+    })
 
-      self.postMessage({ type: 'complete', imageData, duration }, {
-        transfer: [imageData.data.buffer],
-      })
-    } catch (error) {
-      self.postMessage({ type: 'error', message: String(error) })
+    if (!preview.isComplete) {
+      setTimeout(() => renderPass(renderId, width, height, fov, startTime), 0)
     }
+  } catch (error) {
+    self.postMessage({ type: 'error', message: String(error) })
   }
 }
 
 // Initialize WASM
 import('../../dist/wasm/penumbra-simd.js')
   .then((module) => {
-    renderFromBuffer = module.renderFromBuffer
+    preview = new module.PreviewRenderer(module.PreviewMode.Sixteenth)
     wasmReady = true
 
     // Process any queued messages
@@ -4,7 +4,7 @@
 //! and measured using performance.now() for accurate WASM performance profiling.
 
 use crate::light::Light;
-use crate::material::Material;
+use crate::material::{Material, ShadingModel};
 use crate::matrix::Matrix4;
 use crate::ray::Ray;
 use crate::sphere::Sphere;
@@ -254,6 +254,10 @@ impl BenchOps {
             diffuse: 0.8,
             specular: 0.5,
             shininess: 100.0,
+            emission: Tuple::color(0.0, 0.0, 0.0),
+            shading_model: ShadingModel::Phong,
+            roughness: 0.5,
+            metalness: 0.0,
         };
         world.objects.push(s3);
 
@@ -0,0 +1,329 @@
+//! Renders a scene file to an image without a browser.
+//!
+//! Scenes are either Ray Tracer Challenge YAML files (`.yml` or `.yaml`) or the binary
+//! buffer layout that the JavaScript `Scene` wrapper writes to its `SharedArrayBuffer`.
+//! Timing goes to stderr so CI logs can track it.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{env, fs};
+
+use penumbra::camera::{Camera, RenderPass};
+use penumbra::canvas::ImageFormat;
+use penumbra::integrator::Integrator;
+use penumbra::scene::parse_scene_buffer;
+use penumbra::tonemap::ToneMapping;
+use penumbra::world::World;
+use penumbra::yaml::parse_yaml_scene;
+
+const DEFAULT_SIZE: (usize, usize) = (640, 480);
+const DEFAULT_FOV_DEGREES: f32 = 60.0;
+
+const USAGE: &str = "\
+Usage: penumbra [OPTIONS] <SCENE>
+
+Renders a YAML scene (.yml, .yaml) or a scene buffer file to an image.
+
+Options:
+  -o, --output <PATH>      Image to write; .png, .ppm, .pfm or .hdr [default: render.png]
+      --size <WxH>         Image size in pixels [default: from the YAML camera, or 640x480]
+      --fov <DEGREES>      Field of view [default: from the YAML camera, or 60]
+  -s, --samples <N>        Samples per pixel [default: 1]
+      --path-tracing       Trace diffuse bounces instead of direct light only
+      --seed <N>           Seed for sample jitter and path tracing [default: 0]
+      --pass <PASS>        Write depth, normal, albedo or object-id instead of color;
+                           use .pfm to keep the exact values
+      --help               Print this help
+";
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    scene: PathBuf,
+    output: PathBuf,
+    size: Option<(usize, usize)>,
+    fov_degrees: Option<f32>,
+    samples: u32,
+    integrator: Integrator,
+    seed: u64,
+    pass: Option<RenderPass>,
+}
+
+/// Parses command-line arguments, returning `None` when help was requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut scene = None;
+    let mut output = PathBuf::from("render.png");
+    let mut size = None;
+    let mut fov_degrees = None;
+    let mut samples = 1;
+    let mut integrator = Integrator::Whitted;
+    let mut seed = 0;
+    let mut pass = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--help" => return Ok(None),
+            "-o" | "--output" => output = PathBuf::from(value()?),
+            "--size" => size = Some(parse_size(&value()?)?),
+            "--fov" => fov_degrees = Some(parse_number(&arg, &value()?)?),
+            "-s" | "--samples" => samples = parse_number(&arg, &value()?)?,
+            "--path-tracing" => integrator = Integrator::PathTracing,
+            "--seed" => seed = parse_number(&arg, &value()?)?,
+            "--pass" => pass = Some(parse_pass(&value()?)?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            _ if scene.is_some() => return Err(format!("unexpected argument {arg}")),
+            _ => scene = Some(PathBuf::from(arg)),
+        }
+    }
+
+    if samples == 0 {
+        return Err("--samples must be at least 1".to_string());
+    }
+    if pass.is_some() && (samples > 1 || integrator != Integrator::Whitted) {
+        return Err(
+            "--pass cannot be combined with --samples or --path-tracing".to_string()
+        );
+    }
+    Ok(Some(Options {
+        scene: scene.ok_or("no scene file given")?,
+        output,
+        size,
+        fov_degrees,
+        samples,
+        integrator,
+        seed,
+        pass,
+    }))
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {value:?} for {option}"))
+}
+
+/// Parses a `WIDTHxHEIGHT` size such as `1920x1080`.
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("invalid size {value:?}, expected WIDTHxHEIGHT");
+    let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_pass(value: &str) -> Result<RenderPass, String> {
+    match value {
+        "depth" => Ok(RenderPass::Depth),
+        "normal" => Ok(RenderPass::Normal),
+        "albedo" => Ok(RenderPass::Albedo),
+        "object-id" => Ok(RenderPass::ObjectId),
+        _ => Err(format!(
+            "unknown pass {value:?}, expected depth, normal, albedo or object-id"
+        )),
+    }
+}
+
+/// Picks the image format from the output file extension.
+fn format_for(path: &Path) -> Result<ImageFormat, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => Ok(ImageFormat::Png),
+        Some("ppm") => Ok(ImageFormat::Ppm),
+        Some("pfm") => Ok(ImageFormat::Pfm),
+        Some("hdr") => Ok(ImageFormat::Rgbe),
+        _ => Err(format!(
+            "cannot tell the image format of {}; use .png, .ppm, .pfm or .hdr",
+            path.display()
+        )),
+    }
+}
+
+/// Loads the scene, applying any size or field of view given on the command line.
+fn load_scene(options: &Options) -> Result<(Camera, World, ToneMapping), String> {
+    let data = fs::read(&options.scene)
+        .map_err(|e| format!("cannot read {}: {e}", options.scene.display()))?;
+    let is_yaml = matches!(
+        options.scene.extension().and_then(|e| e.to_str()),
+        Some("yml" | "yaml")
+    );
+
+    if !is_yaml {
+        let (width, height) = options.size.unwrap_or(DEFAULT_SIZE);
+        let fov = options
+            .fov_degrees
+            .unwrap_or(DEFAULT_FOV_DEGREES)
+            .to_radians();
+        return parse_scene_buffer(&data, width, height, fov).map_err(|e| e.to_string());
+    }
+
+    let source = String::from_utf8(data).map_err(|_| "scene is not UTF-8 text")?;
+    let (camera, world) = parse_yaml_scene(&source).map_err(|e| e.to_string())?;
+    if options.size.is_none() && options.fov_degrees.is_none() {
+        return Ok((camera, world, ToneMapping::default()));
+    }
+    let (width, height) = options.size.unwrap_or(camera.size());
+    let fov = options
+        .fov_degrees
+        .map_or(camera.field_of_view(), f32::to_radians);
+    let mut resized = Camera::new(width, height, fov);
+    resized.set_transform(camera.transform())?;
+    Ok((resized, world, ToneMapping::default()))
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let format = format_for(&options.output)?;
+    let (camera, world, tone_mapping) =
+        load_scene(options).map_err(|e| format!("{}: {e}", options.scene.display()))?;
+
+    let start = Instant::now();
+    // A single Whitted sample through each pixel center matches the browser renderer
+    let canvas = if let Some(pass) = options.pass {
+        camera.render_pass(&world, pass)
+    } else if options.samples == 1 && options.integrator == Integrator::Whitted {
+        camera.render(&world)
+    } else {
+        camera.render_with(&world, options.integrator, options.samples, options.seed)
+    };
+    let render_time = start.elapsed();
+    let (width, height) = camera.size();
+    // Passes hold data rather than light, so exposure and sRGB encoding do not apply
+    let tone_mapping = if options.pass.is_some() {
+        ToneMapping::default()
+    } else {
+        tone_mapping
+    };
+
+    fs::write(&options.output, canvas.encode(format, tone_mapping))
+        .map_err(|e| format!("cannot write {}: {e}", options.output.display()))?;
+    eprintln!(
+        "Rendered {}x{} at {} samples per pixel in {:.2?}; wrote {}",
+        width,
+        height,
+        options.samples,
+        render_time,
+        options.output.display()
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => match run(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("penumbra: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            print!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("penumbra: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn defaults_apply_when_only_a_scene_is_given() {
+        let options = parse(&["scene.bin"]).unwrap().unwrap();
+        assert_eq!(
+            options,
+            Options {
+                scene: PathBuf::from("scene.bin"),
+                output: PathBuf::from("render.png"),
+                size: None,
+                fov_degrees: None,
+                samples: 1,
+                integrator: Integrator::Whitted,
+                seed: 0,
+                pass: None,
+            }
+        );
+    }
+
+    #[test]
+    fn options_may_come_before_or_after_the_scene() {
+        let args = [
+            "--size",
+            "320x200",
+            "scene.bin",
+            "-o",
+            "out.ppm",
+            "-s",
+            "16",
+            "--path-tracing",
+            "--seed",
+            "7",
+            "--fov",
+            "90",
+        ];
+        let options = parse(&args).unwrap().unwrap();
+        assert_eq!(options.size, Some((320, 200)));
+        assert_eq!(options.output, PathBuf::from("out.ppm"));
+        assert_eq!(options.samples, 16);
+        assert_eq!(options.integrator, Integrator::PathTracing);
+        assert_eq!(options.seed, 7);
+        assert_eq!(options.fov_degrees, Some(90.0));
+    }
+
+    #[test]
+    fn auxiliary_passes_are_selected_by_name() {
+        let options = parse(&["a.yml", "--pass", "object-id"]).unwrap().unwrap();
+        assert_eq!(options.pass, Some(RenderPass::ObjectId));
+        assert!(parse(&["a.yml", "--pass", "shadow"])
+            .unwrap_err()
+            .contains("shadow"));
+        assert!(parse(&["a.yml", "--pass", "depth", "-s", "4"]).is_err());
+        assert!(parse(&["a.yml", "--pass", "normal", "--path-tracing"]).is_err());
+    }
+
+    #[test]
+    fn help_is_not_an_error() {
+        assert_eq!(parse(&["--help"]), Ok(None));
+    }
+
+    #[test]
+    fn invalid_arguments_are_reported() {
+        assert!(parse(&[]).unwrap_err().contains("no scene"));
+        assert!(parse(&["a.bin", "b.bin"]).unwrap_err().contains("b.bin"));
+        assert!(parse(&["a.bin", "--bogus"])
+            .unwrap_err()
+            .contains("--bogus"));
+        assert!(parse(&["a.bin", "--samples"])
+            .unwrap_err()
+            .contains("needs a value"));
+        assert!(parse(&["a.bin", "-s", "many"])
+            .unwrap_err()
+            .contains("many"));
+        assert!(parse(&["a.bin", "-s", "0"]).is_err());
+        assert!(parse(&["a.bin", "--size", "640"]).is_err());
+        assert!(parse(&["a.bin", "--size", "0x480"]).is_err());
+    }
+
+    #[test]
+    fn the_output_extension_selects_the_format() {
+        assert_eq!(format_for(Path::new("a.png")), Ok(ImageFormat::Png));
+        assert_eq!(format_for(Path::new("dir/a.PPM")), Ok(ImageFormat::Ppm));
+        assert_eq!(format_for(Path::new("a.pfm")), Ok(ImageFormat::Pfm));
+        assert_eq!(format_for(Path::new("a.hdr")), Ok(ImageFormat::Rgbe));
+        assert!(format_for(Path::new("a.jpg")).is_err());
+        assert!(format_for(Path::new("render")).is_err());
+    }
+}
@@ -1,13 +1,59 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
 use crate::canvas::*;
+use crate::integrator::Integrator;
 use crate::matrix::*;
 use crate::ray::*;
+use crate::rng::{derive_seed, Rng};
+use crate::transform::view_transform;
 use crate::tuple::*;
 use crate::world::*;
 
+/// How the camera maps canvas pixels to ray directions.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum Projection {
+    /// Pinhole projection through a canvas one unit in front of the eye.
+    #[default]
+    Perspective = 0,
+    /// Full 360°×180° panorama: x maps to longitude and y to latitude.
+    /// The field of view is ignored.
+    Equirectangular = 1,
+}
+
+/// Auxiliary data a render can output in place of shaded color. Pixels where the ray
+/// hits nothing are zero in every pass.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderPass {
+    /// Distance from the eye to the surface along the view axis, in all three
+    /// channels. Equirectangular cameras have no single view axis and store the
+    /// distance along the ray instead.
+    Depth = 0,
+    /// World-space surface normal facing the camera, with components from -1 to 1.
+    Normal = 1,
+    /// Material color without any lighting.
+    Albedo = 2,
+    /// One more than the object's position in the world, in all three channels, so
+    /// that 0 is left for the background. For a world read from a scene buffer this is
+    /// the object ID that `addSphereToBuffer` and `Pick` report, plus one.
+    ObjectId = 3,
+}
+
+#[derive(Clone, Debug)]
 pub struct Camera {
     hsize: usize,
     vsize: usize,
     field_of_view: f32,
+    projection: Projection,
     transform: Matrix4,
     transform_inverse: Matrix4,
     origin: Tuple,
@@ -37,6 +83,7 @@ impl Camera {
             hsize,
             vsize,
             field_of_view,
+            projection: Projection::default(),
             transform,
             transform_inverse,
             origin,
@@ -54,11 +101,36 @@ impl Camera {
         self.field_of_view
     }
 
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn transform(&self) -> Matrix4 {
+        self.transform
+    }
+
     pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
-        // Offset from the edge of the canvas to the pixel’s center
+        self.ray_for_sample(x, y, 0.5, 0.5)
+    }
+
+    /// Like `ray_for_pixel`, but passes through the point (`dx`, `dy`) within the
+    /// pixel, where (0, 0) is its top-left corner and (0.5, 0.5) its center.
+    pub fn ray_for_sample(&self, x: usize, y: usize, dx: f32, dy: f32) -> Ray {
+        match self.projection {
+            Projection::Perspective => self.perspective_ray(x, y, dx, dy),
+            Projection::Equirectangular => self.equirectangular_ray(x, y, dx, dy),
+        }
+    }
+
+    fn perspective_ray(&self, x: usize, y: usize, dx: f32, dy: f32) -> Ray {
+        // Offset from the edge of the canvas to the sample point within the pixel
         let pixel_edge = Tuple::vector(x as f32, y as f32, 0.0);
-        let half_pixel_offset: Tuple = Tuple::vector(0.5, 0.5, 0.0);
-        let offset = (pixel_edge + half_pixel_offset) * self.pixel_size;
+        let sample_offset: Tuple = Tuple::vector(dx, dy, 0.0);
+        let offset = (pixel_edge + sample_offset) * self.pixel_size;
 
         // Untransformed coords of the pixel in world space (the pixel is on the canvas at z
         // = -1)
@@ -70,6 +142,22 @@ impl Camera {
         Ray::new(self.origin, direction)
     }
 
+    fn equirectangular_ray(&self, x: usize, y: usize, dx: f32, dy: f32) -> Ray {
+        // Longitude sweeps -π..π left to right with 0 straight ahead (-z); latitude
+        // sweeps π/2..-π/2 top to bottom. As in `perspective_ray`, +x in camera space
+        // is the left of the image, so longitude turns towards -x.
+        let longitude = ((x as f32 + dx) / self.hsize as f32) * 2.0 * PI - PI;
+        let latitude = FRAC_PI_2 - ((y as f32 + dy) / self.vsize as f32) * PI;
+
+        let direction = Tuple::vector(
+            -latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos(),
+        );
+        let direction = (self.transform_inverse * direction).normalize();
+        Ray::new(self.origin, direction)
+    }
+
     pub fn set_transform(&mut self, transform: Matrix4) -> Result<(), &'static str> {
         self.transform = transform;
         self.transform_inverse = transform
@@ -79,18 +167,330 @@ impl Camera {
         Ok(())
     }
 
+    /// Derives left and right eye cameras for stereo rendering.
+    ///
+    /// The eyes sit `interocular` apart along the camera’s horizontal axis and toe in
+    /// toward a point `convergence` units straight ahead. An infinite convergence
+    /// distance keeps the eyes parallel.
+    pub fn stereo_pair(
+        &self,
+        interocular: f32,
+        convergence: f32,
+    ) -> Result<(Camera, Camera), &'static str> {
+        // Camera space +x is the camera’s left (see `view_transform`)
+        let left = self.eye(interocular / 2.0, convergence)?;
+        let right = self.eye(-interocular / 2.0, convergence)?;
+        Ok((left, right))
+    }
+
+    fn eye(&self, offset: f32, convergence: f32) -> Result<Camera, &'static str> {
+        let from = Tuple::point(offset, 0.0, 0.0);
+        let to = if convergence.is_finite() {
+            Tuple::point(0.0, 0.0, -convergence)
+        } else {
+            Tuple::point(offset, 0.0, -1.0)
+        };
+        let eye_view = view_transform(from, to, Tuple::vector(0.0, 1.0, 0.0));
+
+        let mut eye = self.clone();
+        eye.set_transform(eye_view * self.transform)?;
+        Ok(eye)
+    }
+
+    /// Renders the world once per eye, returning the left and right images.
+    pub fn render_stereo(
+        &self,
+        world: &World,
+        interocular: f32,
+        convergence: f32,
+    ) -> Result<(Canvas, Canvas), &'static str> {
+        let (left, right) = self.stereo_pair(interocular, convergence)?;
+        Ok((left.render(world), right.render(world)))
+    }
+
     pub fn render(&self, world: &World) -> Canvas {
-        let mut image = Canvas::new(self.hsize, self.vsize);
+        self.render_tile(world, Tile::new(0, 0, self.hsize, self.vsize))
+    }
+
+    /// Finds what the ray through the center of pixel (`x`, `y`) hits first, without
+    /// shading it. Returns `None` if the ray misses or the pixel is off the canvas.
+    pub fn pick(&self, world: &World, x: usize, y: usize) -> Option<Pick> {
+        if x >= self.hsize || y >= self.vsize {
+            return None;
+        }
+        let ray = self.ray_for_pixel(x, y);
+        let intersections = world.intersect(ray);
+        let hit = intersections.hit()?;
+        let object_index = hit.object_index?;
+        let comps = hit.prepare_computations(ray);
+        Some(Pick {
+            object_id: object_index as u32,
+            sphere_id: hit.object.id(),
+            point: comps.point,
+            normal: comps.normalv,
+            distance: comps.t,
+        })
+    }
+
+    /// Renders only the `width`×`height` rectangle whose top-left pixel is (`x`, `y`).
+    ///
+    /// The returned canvas is the size of the region, and its pixels match the
+    /// corresponding pixels of a full `render`.
+    pub fn render_region(
+        &self,
+        world: &World,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<Canvas, &'static str> {
+        let right = x.checked_add(width);
+        let bottom = y.checked_add(height);
+        if right.is_none_or(|right| right > self.hsize)
+            || bottom.is_none_or(|bottom| bottom > self.vsize)
+        {
+            return Err("region must lie within the camera’s canvas");
+        }
+        Ok(self.render_tile(world, Tile::new(x, y, width, height)))
+    }
+
+    /// Splits the camera’s canvas into tiles of at most `tile_width`×`tile_height`,
+    /// row by row from the top left. Tiles along the right and bottom edges are
+    /// clipped to the canvas. Fails if either tile dimension is 0.
+    pub fn tiles(
+        &self,
+        tile_width: usize,
+        tile_height: usize,
+    ) -> Result<Tiles, &'static str> {
+        Tiles::new(self.hsize, self.vsize, tile_width, tile_height)
+    }
+
+    /// Renders `samples` jittered samples per pixel with `integrator` and averages them.
+    ///
+    /// Each row draws from its own random sequence derived from `seed`, so the image is
+    /// reproducible regardless of how rows are scheduled.
+    pub fn render_with(
+        &self,
+        world: &World,
+        integrator: Integrator,
+        samples: u32,
+        seed: u64,
+    ) -> Canvas {
+        let samples = samples.max(1);
+        fill_rows(self.hsize, self.vsize, |y, row| {
+            let mut rng = Rng::new(derive_seed(seed, y as u64));
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let mut sum = [0.0; 3];
+                for _ in 0..samples {
+                    let (dx, dy) = (rng.next_f32(), rng.next_f32());
+                    let ray = self.ray_for_sample(x, y, dx, dy);
+                    let color = integrator.color_at(world, ray, &mut rng);
+                    sum[0] += color.x();
+                    sum[1] += color.y();
+                    sum[2] += color.z();
+                }
+                let [r, g, b] = sum.map(|channel| channel / samples as f32);
+                *pixel = Tuple::color(r, g, b);
+            }
+        })
+    }
+
+    /// Renders an auxiliary pass, tracing one ray through the center of each pixel.
+    pub fn render_pass(&self, world: &World, pass: RenderPass) -> Canvas {
+        fill_rows(self.hsize, self.vsize, |y, row| {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = self.pass_at(world, pass, self.ray_for_pixel(x, y));
+            }
+        })
+    }
 
-        for x in 0..self.hsize {
-            for y in 0..self.vsize {
-                let ray = self.ray_for_pixel(x, y);
-                let color = world.color_at(ray);
-                image.write_pixel(x, y, color);
+    /// The value of `pass` where `ray` first hits `world`.
+    fn pass_at(&self, world: &World, pass: RenderPass, ray: Ray) -> Tuple {
+        let intersections = world.intersect(ray);
+        let Some(hit) = intersections.hit() else {
+            return Tuple::color(0.0, 0.0, 0.0);
+        };
+        let comps = hit.prepare_computations(ray);
+        let gray = |value: f32| Tuple::color(value, value, value);
+        match pass {
+            RenderPass::Depth => match self.projection {
+                Projection::Perspective => gray(-(self.transform * comps.point).z()),
+                Projection::Equirectangular => gray(comps.t),
+            },
+            RenderPass::Normal => {
+                let normal = comps.normalv;
+                Tuple::color(normal.x(), normal.y(), normal.z())
             }
+            RenderPass::Albedo => comps.object.material.color,
+            RenderPass::ObjectId => gray(hit.object_index.map_or(0.0, |i| (i + 1) as f32)),
+        }
+    }
+
+    fn render_tile(&self, world: &World, tile: Tile) -> Canvas {
+        fill_rows(tile.width, tile.height, |y, row| {
+            self.render_row(world, tile, y, row)
+        })
+    }
+
+    /// Traces row `y` of `tile` into `row`, which holds `tile.width` pixels.
+    pub(crate) fn render_row(
+        &self,
+        world: &World,
+        tile: Tile,
+        y: usize,
+        row: &mut [Tuple],
+    ) {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let ray = self.ray_for_pixel(tile.x + x, tile.y + y);
+            *pixel = world.color_at(ray);
         }
+    }
+}
+
+/// Creates a `width`×`height` canvas and fills each row with `render_row(y, row)`.
+fn fill_rows<F>(width: usize, height: usize, render_row: F) -> Canvas
+where
+    F: Fn(usize, &mut [Tuple]) + Sync,
+{
+    let mut image = Canvas::new(width, height);
+    if width == 0 {
+        return image;
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
 
         image
+            .pixels_mut()
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| render_row(y, row));
+    }
+
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    for (y, row) in image.pixels_mut().chunks_mut(width).enumerate() {
+        render_row(y, row);
+    }
+
+    image
+}
+
+/// The surface under a pixel, as found by `Camera::pick`.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pick {
+    /// Position of the hit object in the world, which for a world read from a scene
+    /// buffer is the object ID that `addSphereToBuffer` returned for it.
+    #[cfg_attr(feature = "web", wasm_bindgen(js_name = objectId))]
+    pub object_id: u32,
+    /// `Sphere::id` of the hit sphere, which the wasm `Scene` hands out from
+    /// `addSphere`. Spheres read from a scene buffer get new IDs on every parse, so
+    /// use `object_id` for those.
+    #[cfg_attr(feature = "web", wasm_bindgen(js_name = sphereId))]
+    pub sphere_id: u64,
+    /// Where the ray hit the sphere, in world space.
+    #[cfg_attr(feature = "web", wasm_bindgen(skip))]
+    pub point: Tuple,
+    /// Surface normal at `point`, facing the camera.
+    #[cfg_attr(feature = "web", wasm_bindgen(skip))]
+    pub normal: Tuple,
+    /// Distance from the camera to `point`.
+    pub distance: f32,
+}
+
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+impl Pick {
+    /// The hit point as `[x, y, z]`.
+    #[wasm_bindgen(getter = point)]
+    pub fn point_xyz(&self) -> Vec<f32> {
+        vec![self.point.x(), self.point.y(), self.point.z()]
+    }
+
+    /// The surface normal as `[x, y, z]`.
+    #[wasm_bindgen(getter = normal)]
+    pub fn normal_xyz(&self) -> Vec<f32> {
+        vec![self.normal.x(), self.normal.y(), self.normal.z()]
+    }
+}
+
+/// A rectangular region of a camera’s canvas, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Tile {
+        Tile {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Iterator over the tiles covering a canvas. See `Camera::tiles`.
+#[derive(Clone, Debug)]
+pub struct Tiles {
+    hsize: usize,
+    vsize: usize,
+    tile_width: usize,
+    tile_height: usize,
+    x: usize,
+    y: usize,
+}
+
+impl Tiles {
+    /// Tiles an `hsize`×`vsize` canvas. Fails if either tile dimension is 0, since
+    /// such tiles would never cover it.
+    pub fn new(
+        hsize: usize,
+        vsize: usize,
+        tile_width: usize,
+        tile_height: usize,
+    ) -> Result<Tiles, &'static str> {
+        if tile_width == 0 || tile_height == 0 {
+            return Err("tile dimensions must be non-zero");
+        }
+        Ok(Tiles {
+            hsize,
+            vsize,
+            tile_width,
+            tile_height,
+            x: 0,
+            y: 0,
+        })
+    }
+}
+
+impl Iterator for Tiles {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        if self.y >= self.vsize || self.hsize == 0 {
+            return None;
+        }
+
+        let tile = Tile::new(
+            self.x,
+            self.y,
+            self.tile_width.min(self.hsize - self.x),
+            self.tile_height.min(self.vsize - self.y),
+        );
+
+        self.x += self.tile_width;
+        if self.x >= self.hsize {
+            self.x = 0;
+            self.y += self.tile_height;
+        }
+
+        Some(tile)
     }
 }
 
@@ -101,7 +501,7 @@ mod tests {
     use crate::transform::{view_transform, Transform};
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn constructing_a_camera() {
         let hsize = 160;
         let vsize = 120;
@@ -113,19 +513,19 @@ mod tests {
         assert!(c.transform == Matrix4::identity());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_pixel_size_for_a_horizontal_canvas() {
         let c = Camera::new(200, 125, std::f32::consts::FRAC_PI_2);
         assert!(fuzzy_eq_f32(c.pixel_size, 0.01));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_pixel_size_for_a_vertical_canvas() {
         let c = Camera::new(125, 200, std::f32::consts::FRAC_PI_2);
         assert!(fuzzy_eq_f32(c.pixel_size, 0.01));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn ray_through_center_of_canvas() {
         let c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
         let r = c.ray_for_pixel(100, 50);
@@ -133,7 +533,7 @@ mod tests {
         assert_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn ray_through_corner_of_canvas() {
         let c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
         let r = c.ray_for_pixel(0, 0);
@@ -141,7 +541,7 @@ mod tests {
         assert_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn ray_when_camera_is_transformed() {
         let mut c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
         c.set_transform(
@@ -159,7 +559,17 @@ mod tests {
         );
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn ray_through_a_pixel_corner() {
+        let c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
+        let r = c.ray_for_sample(0, 0, 0.0, 0.0);
+        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+        // The top-left corner of the canvas is at (half_width, half_height, -1)
+        let corner = Tuple::vector(1.0, 101.0 / 201.0, -1.0);
+        assert_eq!(r.direction, corner.normalize());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
     fn rendering_a_world_with_a_camera() {
         let w = World::default();
         let mut c = Camera::new(11, 11, std::f32::consts::FRAC_PI_2);
@@ -174,4 +584,345 @@ mod tests {
         let pixel_color = image.pixel_at(5, 5);
         assert!(pixel_color.rgb_eq(expected_color));
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_auxiliary_passes() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+
+        let center = |pass| c.render_pass(&w, pass).pixel_at(5, 5);
+        assert!(center(RenderPass::Depth).rgb_eq(Tuple::color(4.0, 4.0, 4.0)));
+        assert!(center(RenderPass::Normal).rgb_eq(Tuple::color(0.0, 0.0, -1.0)));
+        assert_eq!(center(RenderPass::Albedo), w.objects[0].material.color);
+        assert_eq!(center(RenderPass::ObjectId), Tuple::color(1.0, 1.0, 1.0));
+
+        for pass in [
+            RenderPass::Depth,
+            RenderPass::Normal,
+            RenderPass::Albedo,
+            RenderPass::ObjectId,
+        ] {
+            let background = c.render_pass(&w, pass).pixel_at(0, 0);
+            assert_eq!(background, Tuple::color(0.0, 0.0, 0.0));
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn depth_is_measured_along_the_view_axis() {
+        let mut w = World::new();
+        let mut sphere = crate::sphere::Sphere::new();
+        sphere
+            .set_transform(Transform::new().scale(10.0, 10.0, 1.0))
+            .unwrap();
+        w.objects.push(sphere);
+        let mut c = Camera::new(11, 11, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+
+        // Off-center rays travel further than the depth of the point they hit
+        let depth = c.render_pass(&w, RenderPass::Depth);
+        let ray = c.ray_for_pixel(8, 5);
+        let t = w.intersect(ray).hit().unwrap().t;
+        assert!(depth.pixel_at(8, 5).x() < t);
+        let hit = ray.position(t);
+        assert!(fuzzy_eq_f32(depth.pixel_at(8, 5).x(), hit.z() + 5.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn picking_the_object_under_a_pixel() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+
+        let pick = c.pick(&w, 5, 5).unwrap();
+        assert_eq!(pick.object_id, 0);
+        assert_eq!(pick.sphere_id, w.objects[0].id());
+        assert_eq!(pick.point, Tuple::point(0.0, 0.0, -1.0));
+        assert_eq!(pick.normal, Tuple::vector(0.0, 0.0, -1.0));
+        assert!(fuzzy_eq_f32(pick.distance, 4.0));
+
+        assert_eq!(c.pick(&w, 0, 0), None);
+        assert_eq!(c.pick(&w, 11, 5), None);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parallel_stereo_eyes_are_offset_horizontally() {
+        let c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
+        let (left, right) = c.stereo_pair(0.1, f32::INFINITY).unwrap();
+
+        let l = left.ray_for_pixel(100, 50);
+        let r = right.ray_for_pixel(100, 50);
+        assert_eq!(l.origin, Tuple::point(0.05, 0.0, 0.0));
+        assert_eq!(r.origin, Tuple::point(-0.05, 0.0, 0.0));
+        assert_eq!(l.direction, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn converging_stereo_eyes_look_at_the_convergence_point() {
+        let mut c = Camera::new(201, 101, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        let (left, right) = c.stereo_pair(1.0, 5.0).unwrap();
+
+        let target = Tuple::point(0.0, 0.0, 0.0);
+        let l = left.ray_for_pixel(100, 50);
+        let r = right.ray_for_pixel(100, 50);
+        assert_eq!(l.direction, (target - l.origin).normalize());
+        assert_eq!(r.direction, (target - r.origin).normalize());
+        assert!(fuzzy_eq_f32((l.origin - r.origin).magnitude(), 1.0));
+        assert!(fuzzy_eq_f32(l.origin.z(), -5.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_a_stereo_pair() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        let (left, right) = c.render_stereo(&w, 0.0, 5.0).unwrap();
+        let mono = c.render(&w);
+        assert!(left.pixel_at(5, 5).rgb_eq(mono.pixel_at(5, 5)));
+        assert!(right.pixel_at(5, 5).rgb_eq(mono.pixel_at(5, 5)));
+    }
+
+    fn default_world_camera(hsize: usize, vsize: usize) -> Camera {
+        let mut c = Camera::new(hsize, vsize, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        c
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_a_region_matches_the_full_render() {
+        let w = World::default();
+        let c = default_world_camera(11, 11);
+        let full = c.render(&w);
+
+        let region = c.render_region(&w, 3, 4, 5, 2).unwrap();
+        assert_eq!(region.width, 5);
+        assert_eq!(region.height, 2);
+        for y in 0..2 {
+            for x in 0..5 {
+                assert_eq!(region.pixel_at(x, y), full.pixel_at(x + 3, y + 4));
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_a_region_outside_the_canvas_is_an_error() {
+        let w = World::default();
+        let c = default_world_camera(11, 11);
+        assert!(c.render_region(&w, 8, 0, 4, 1).is_err());
+        assert!(c.render_region(&w, 0, 11, 1, 1).is_err());
+        assert!(c.render_region(&w, 1, 0, usize::MAX, 1).is_err());
+        assert!(c.render_region(&w, 0, 1, 1, usize::MAX).is_err());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn tiles_cover_the_canvas_and_clip_at_the_edges() {
+        let c = Camera::new(5, 3, std::f32::consts::FRAC_PI_2);
+        let tiles: Vec<Tile> = c.tiles(2, 2).unwrap().collect();
+        assert_eq!(
+            tiles,
+            vec![
+                Tile::new(0, 0, 2, 2),
+                Tile::new(2, 0, 2, 2),
+                Tile::new(4, 0, 1, 2),
+                Tile::new(0, 2, 2, 1),
+                Tile::new(2, 2, 2, 1),
+                Tile::new(4, 2, 1, 1),
+            ]
+        );
+        assert!(c.tiles(0, 2).is_err());
+        assert!(c.tiles(2, 0).is_err());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn stitching_rendered_tiles_reproduces_the_full_render() {
+        let w = World::default();
+        let c = default_world_camera(11, 7);
+        let full = c.render(&w);
+
+        let mut stitched = Canvas::new(11, 7);
+        for tile in c.tiles(4, 3).unwrap() {
+            let part = c
+                .render_region(&w, tile.x, tile.y, tile.width, tile.height)
+                .unwrap();
+            stitched.blit(&part, tile.x, tile.y);
+        }
+        for y in 0..7 {
+            for x in 0..11 {
+                assert_eq!(stitched.pixel_at(x, y), full.pixel_at(x, y));
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cameras_and_worlds_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Camera>();
+        assert_send_sync::<World>();
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_matches_tracing_each_pixel_individually() {
+        let w = World::default();
+        let c = default_world_camera(23, 17);
+        let image = c.render(&w);
+        for y in 0..17 {
+            for x in 0..23 {
+                let expected = w.color_at(c.ray_for_pixel(x, y));
+                assert_eq!(image.pixel_at(x, y).as_array(), expected.as_array());
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_with_an_integrator_is_reproducible_for_a_seed() {
+        let w = World::default();
+        let c = default_world_camera(9, 7);
+        let a = c.render_with(&w, Integrator::PathTracing, 2, 42);
+        let b = c.render_with(&w, Integrator::PathTracing, 2, 42);
+        for y in 0..7 {
+            for x in 0..9 {
+                assert_eq!(a.pixel_at(x, y).as_array(), b.pixel_at(x, y).as_array());
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn whitted_samples_average_close_to_the_center_sampled_render() {
+        let w = World::default();
+        let c = default_world_camera(41, 41);
+        let image = c.render_with(&w, Integrator::Whitted, 16, 1);
+        let single = c.render(&w);
+        assert!((image.pixel_at(20, 20).x() - single.pixel_at(20, 20).x()).abs() < 0.02);
+        assert_eq!(image.pixel_at(0, 0), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn a_camera_defaults_to_perspective_projection() {
+        let c = Camera::new(160, 120, std::f32::consts::FRAC_PI_2);
+        assert_eq!(c.projection(), Projection::Perspective);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn equirectangular_ray_through_center_looks_forward() {
+        let mut c = Camera::new(361, 181, std::f32::consts::FRAC_PI_2);
+        c.set_projection(Projection::Equirectangular);
+        let r = c.ray_for_pixel(180, 90);
+        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn equirectangular_rays_cover_the_full_sphere() {
+        let mut c = Camera::new(4, 2, std::f32::consts::FRAC_PI_2);
+        c.set_projection(Projection::Equirectangular);
+
+        // Pixel centers sit at longitudes -135°, -45°, 45°, 135° and latitudes ±45°
+        let half = 0.5;
+        let r = c.ray_for_pixel(0, 0);
+        assert_eq!(r.direction, Tuple::vector(half, 2f32.sqrt() / 2.0, half));
+        let r = c.ray_for_pixel(1, 1);
+        assert_eq!(
+            r.direction,
+            Tuple::vector(half, -(2f32.sqrt()) / 2.0, -half)
+        );
+        let r = c.ray_for_pixel(2, 0);
+        assert_eq!(r.direction, Tuple::vector(-half, 2f32.sqrt() / 2.0, -half));
+        let r = c.ray_for_pixel(3, 1);
+        assert_eq!(
+            r.direction,
+            Tuple::vector(-half, -(2f32.sqrt()) / 2.0, half)
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn both_projections_put_an_object_on_the_same_side() {
+        let mut w = World::new();
+        let mut sphere = crate::sphere::Sphere::new();
+        sphere
+            .set_transform(Transform::new().translate(2.0, 0.0, 0.0))
+            .unwrap();
+        w.objects.push(sphere);
+
+        let column_of_hit = |projection| {
+            let mut c = Camera::new(40, 20, std::f32::consts::FRAC_PI_2);
+            c.set_projection(projection);
+            c.set_transform(view_transform(
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::point(0.0, 0.0, 0.0),
+                Tuple::vector(0.0, 1.0, 0.0),
+            ))
+            .unwrap();
+            (0..40)
+                .find(|&x| c.pick(&w, x, 10).is_some())
+                .expect("the sphere is in view")
+        };
+
+        // The sphere sits to the right of the view axis in both images
+        assert!(column_of_hit(Projection::Perspective) > 20);
+        assert!(column_of_hit(Projection::Equirectangular) > 20);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn equirectangular_ray_when_camera_is_transformed() {
+        let mut c = Camera::new(361, 181, std::f32::consts::FRAC_PI_2);
+        c.set_projection(Projection::Equirectangular);
+        c.set_transform(
+            Transform::new()
+                .translate(0.0, -2.0, 5.0)
+                .rotate_y(std::f32::consts::FRAC_PI_2)
+                .build(),
+        )
+        .unwrap();
+        let r = c.ray_for_pixel(180, 90);
+        assert_eq!(r.origin, Tuple::point(0.0, 2.0, -5.0));
+        assert_eq!(r.direction, Tuple::vector(1.0, 0.0, 0.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_a_world_with_an_equirectangular_camera() {
+        let w = World::default();
+        let mut c = Camera::new(36, 18, std::f32::consts::FRAC_PI_2);
+        c.set_projection(Projection::Equirectangular);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        let image = c.render(&w);
+        assert!(!image.pixel_at(18, 9).rgb_eq(Tuple::color(0.0, 0.0, 0.0)));
+        assert!(image.pixel_at(0, 9).rgb_eq(Tuple::color(0.0, 0.0, 0.0)));
+    }
 }
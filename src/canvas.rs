@@ -1,7 +1,27 @@
+#[cfg(feature = "web")]
+use js_sys::Uint8ClampedArray;
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "web")]
 use web_sys::ImageData;
 
+use crate::tonemap::ToneMapping;
 use crate::tuple::Tuple;
 
+/// File formats a canvas can be encoded to.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Portable Float Map: 32-bit float channels.
+    Pfm = 0,
+    /// Radiance RGBE (`.hdr`): shared-exponent 8-bit channels.
+    Rgbe = 1,
+    /// Binary Netpbm pixmap (`P6`): tone-mapped 8-bit channels.
+    Ppm = 2,
+    /// PNG: tone-mapped 8-bit RGB.
+    Png = 3,
+}
+
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
@@ -26,17 +46,49 @@ impl Canvas {
         self.pixels[y * self.width + x]
     }
 
-    pub fn to_image_data(&self) -> ImageData {
-        let mut data: Vec<u8> = Vec::with_capacity(self.width * self.height * 4);
-        for pixel in &self.pixels {
-            let rgba = pixel.to_rgba();
-            data.extend_from_slice(&[
-                rgba.x() as u8,
-                rgba.y() as u8,
-                rgba.z() as u8,
-                255_u8,
-            ]);
+    /// All pixels in row-major order.
+    pub(crate) fn pixels_mut(&mut self) -> &mut [Tuple] {
+        &mut self.pixels
+    }
+
+    /// Copies all of `source` into this canvas with its top-left corner at (`x`, `y`).
+    ///
+    /// # Panics
+    /// Panics if `source` does not fit within this canvas at that position.
+    pub fn blit(&mut self, source: &Canvas, x: usize, y: usize) {
+        assert!(
+            x + source.width <= self.width && y + source.height <= self.height,
+            "source canvas must fit within the destination"
+        );
+        for row in 0..source.height {
+            let src = row * source.width;
+            let dst = (y + row) * self.width + x;
+            self.pixels[dst..dst + source.width]
+                .copy_from_slice(&source.pixels[src..src + source.width]);
         }
+    }
+
+    /// Places two equally tall canvases next to each other, `left` first.
+    pub fn side_by_side(left: &Canvas, right: &Canvas) -> Canvas {
+        assert_eq!(
+            left.height, right.height,
+            "canvases must have the same height"
+        );
+        let mut canvas = Canvas::new(left.width + right.width, left.height);
+        canvas.blit(left, 0, 0);
+        canvas.blit(right, left.width, 0);
+        canvas
+    }
+
+    #[cfg(feature = "web")]
+    pub fn to_image_data(&self) -> ImageData {
+        self.to_image_data_with(ToneMapping::default())
+    }
+
+    /// Converts to ImageData after applying exposure, tone mapping and encoding.
+    #[cfg(feature = "web")]
+    pub fn to_image_data_with(&self, tone_mapping: ToneMapping) -> ImageData {
+        let data = self.to_rgba8(tone_mapping);
         ImageData::new_with_u8_clamped_array_and_sh(
             wasm_bindgen::Clamped(&data),
             self.width as u32,
@@ -44,14 +96,84 @@ impl Canvas {
         )
         .expect("Canvas dimensions should be valid for ImageData creation")
     }
+
+    /// Copies the bytes of `to_rgba8` into a JavaScript array, such as a view of a
+    /// `SharedArrayBuffer`, with one copy out of WebAssembly memory.
+    ///
+    /// `scratch` holds the bytes on the way and keeps its allocation between frames.
+    /// `pixels` must hold exactly four bytes per canvas pixel.
+    #[cfg(feature = "web")]
+    pub fn copy_rgba8_into(
+        &self,
+        tone_mapping: ToneMapping,
+        scratch: &mut Vec<u8>,
+        pixels: &Uint8ClampedArray,
+    ) -> Result<(), &'static str> {
+        scratch.resize(self.pixels.len() * 4, 0);
+        self.write_rgba8(tone_mapping, scratch)?;
+        if pixels.length() as usize != scratch.len() {
+            return Err("pixel buffer must hold four bytes per canvas pixel");
+        }
+        pixels.copy_from(scratch);
+        Ok(())
+    }
+
+    /// Encodes the canvas as an image file in `format`.
+    ///
+    /// `tone_mapping` only applies to 8-bit formats; HDR formats store linear colors.
+    pub fn encode(&self, format: ImageFormat, tone_mapping: ToneMapping) -> Vec<u8> {
+        match format {
+            ImageFormat::Pfm => self.to_pfm(),
+            ImageFormat::Rgbe => self.to_rgbe(),
+            ImageFormat::Ppm => self.to_ppm_binary(tone_mapping),
+            ImageFormat::Png => self.to_png(tone_mapping),
+        }
+    }
+
+    /// Opaque 8-bit RGBA bytes in row-major order.
+    pub fn to_rgba8(&self, tone_mapping: ToneMapping) -> Vec<u8> {
+        let mut data = vec![0; self.pixels.len() * 4];
+        self.write_rgba8(tone_mapping, &mut data)
+            .expect("data is sized for the canvas");
+        data
+    }
+
+    /// Linear red, green and blue channels of every pixel in row-major order, without
+    /// tone mapping.
+    pub fn to_rgb_f32(&self) -> Vec<f32> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| [pixel.x(), pixel.y(), pixel.z()])
+            .collect()
+    }
+
+    /// Writes the bytes of `to_rgba8` into `pixels` instead of a new vector.
+    ///
+    /// `pixels` must hold exactly four bytes per canvas pixel.
+    pub fn write_rgba8(
+        &self,
+        tone_mapping: ToneMapping,
+        pixels: &mut [u8],
+    ) -> Result<(), &'static str> {
+        if pixels.len() != self.pixels.len() * 4 {
+            return Err("pixel buffer must hold four bytes per canvas pixel");
+        }
+        let (rgba, _) = pixels.as_chunks_mut::<4>();
+        for (rgba, pixel) in rgba.iter_mut().zip(&self.pixels) {
+            let [r, g, b] = tone_mapping.to_rgb8(*pixel);
+            *rgba = [r, g, b, 255_u8];
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tonemap::ToneMapper;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn create_canvas() {
         let c = Canvas::new(10, 20);
         assert_eq!(c.width, 10);
@@ -61,11 +183,73 @@ mod tests {
         }
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn write_pixel_to_canvas() {
         let mut c = Canvas::new(10, 20);
         let red = Tuple::color(1.0, 0.0, 0.0);
         c.write_pixel(2, 3, red);
         assert_eq!(c.pixel_at(2, 3), red);
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn converting_to_rgba_bytes() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(0, 0, Tuple::color(1.5, 0.5, -0.5));
+        c.write_pixel(1, 0, Tuple::color(0.0, 0.2, 1.0));
+        assert_eq!(
+            c.to_rgba8(ToneMapping::default()),
+            vec![255, 128, 0, 255, 0, 51, 255, 255]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn converting_to_rgba_bytes_with_tone_mapping() {
+        let mut c = Canvas::new(1, 1);
+        c.write_pixel(0, 0, Tuple::color(1.0, 3.0, 0.0));
+        let tone_mapping = ToneMapping::new(ToneMapper::Reinhard, 0.0, false);
+        assert_eq!(c.to_rgba8(tone_mapping), vec![128, 191, 0, 255]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn writing_rgba_bytes_into_a_buffer() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(1, 0, Tuple::color(0.0, 0.2, 1.0));
+        let mut pixels = [7; 8];
+        c.write_rgba8(ToneMapping::default(), &mut pixels).unwrap();
+        assert_eq!(pixels, [0, 0, 0, 255, 0, 51, 255, 255]);
+
+        assert!(c.write_rgba8(ToneMapping::default(), &mut [0; 12]).is_err());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn blitting_a_canvas_into_another() {
+        let red = Tuple::color(1.0, 0.0, 0.0);
+        let mut src = Canvas::new(2, 2);
+        src.write_pixel(0, 0, red);
+        src.write_pixel(1, 1, red);
+
+        let mut c = Canvas::new(5, 4);
+        c.blit(&src, 3, 2);
+        assert_eq!(c.pixel_at(3, 2), red);
+        assert_eq!(c.pixel_at(4, 3), red);
+        assert_eq!(c.pixel_at(4, 2), Tuple::color(0.0, 0.0, 0.0));
+        assert_eq!(c.pixel_at(0, 0), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn placing_canvases_side_by_side() {
+        let red = Tuple::color(1.0, 0.0, 0.0);
+        let blue = Tuple::color(0.0, 0.0, 1.0);
+        let mut left = Canvas::new(2, 3);
+        let mut right = Canvas::new(4, 3);
+        left.write_pixel(1, 2, red);
+        right.write_pixel(0, 2, blue);
+
+        let c = Canvas::side_by_side(&left, &right);
+        assert_eq!(c.width, 6);
+        assert_eq!(c.height, 3);
+        assert_eq!(c.pixel_at(1, 2), red);
+        assert_eq!(c.pixel_at(2, 2), blue);
+        assert_eq!(c.pixel_at(5, 2), Tuple::color(0.0, 0.0, 0.0));
+    }
 }
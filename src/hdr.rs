@@ -0,0 +1,149 @@
+//! High dynamic range image export.
+//!
+//! Both formats keep colors brighter than white and the full precision of the render,
+//! for post-processing in compositing software:
+//!
+//! * Portable Float Map stores raw little-endian `f32` channels.
+//! * Radiance RGBE (`.hdr`) stores an 8-bit mantissa per channel with a shared
+//!   exponent, a quarter of the size at about 1% precision.
+
+use crate::canvas::Canvas;
+use crate::tuple::Tuple;
+
+impl Canvas {
+    /// Encodes the canvas as a color Portable Float Map (`PF`).
+    ///
+    /// PFM stores rows from the bottom up; a negative scale marks little-endian data.
+    pub fn to_pfm(&self) -> Vec<u8> {
+        let header = format!("PF\n{} {}\n-1.0\n", self.width, self.height);
+        let mut data = Vec::with_capacity(header.len() + self.width * self.height * 12);
+        data.extend_from_slice(header.as_bytes());
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let pixel = self.pixel_at(x, y);
+                for channel in [pixel.x(), pixel.y(), pixel.z()] {
+                    data.extend_from_slice(&channel.to_le_bytes());
+                }
+            }
+        }
+        data
+    }
+
+    /// Encodes the canvas as an uncompressed Radiance RGBE (`.hdr`) image.
+    ///
+    /// Negative channels cannot be represented and are written as zero.
+    pub fn to_rgbe(&self) -> Vec<u8> {
+        let header = format!(
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            self.height, self.width
+        );
+        let mut data = Vec::with_capacity(header.len() + self.width * self.height * 4);
+        data.extend_from_slice(header.as_bytes());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                data.extend_from_slice(&rgbe(self.pixel_at(x, y)));
+            }
+        }
+        data
+    }
+}
+
+/// Packs a color into three mantissas sharing the exponent of its largest channel.
+fn rgbe(color: Tuple) -> [u8; 4] {
+    let [r, g, b] = [color.x(), color.y(), color.z()].map(|c| c.max(0.0));
+    let max = r.max(g).max(b);
+    if max < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    // Split max into mantissa × 2^exponent with the mantissa in [0.5, 1). The shared
+    // exponent byte tops out at 2^127, so brighter channels (and infinity) saturate to
+    // a mantissa of 255 instead of wrapping around to a tiny exponent.
+    let exponent = (((max.to_bits() >> 23) & 0xff) as i32 - 126).min(127);
+    let scale = 256.0 / (exponent as f32).exp2();
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn split_header(data: &[u8], lines: usize) -> (&str, &[u8]) {
+        let end = data
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+            .nth(lines - 1)
+            .map(|(i, _)| i + 1)
+            .unwrap();
+        (std::str::from_utf8(&data[..end]).unwrap(), &data[end..])
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn pfm_header_describes_a_little_endian_color_image() {
+        let data = Canvas::new(5, 3).to_pfm();
+        let (header, pixels) = split_header(&data, 3);
+        assert_eq!(header, "PF\n5 3\n-1.0\n");
+        assert_eq!(pixels.len(), 5 * 3 * 12);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn pfm_stores_rows_bottom_up_as_raw_floats() {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(0, 0, Tuple::color(1.5, -2.0, 100.0));
+        c.write_pixel(1, 1, Tuple::color(0.25, 0.5, 0.75));
+        let data = c.to_pfm();
+        let (_, pixels) = split_header(&data, 3);
+        let floats: Vec<f32> = pixels
+            .chunks(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        // Bottom row first: (0, 1) then (1, 1), then the top row
+        assert_eq!(&floats[3..6], &[0.25, 0.5, 0.75]);
+        assert_eq!(&floats[6..9], &[1.5, -2.0, 100.0]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rgbe_header_describes_top_down_rows() {
+        let data = Canvas::new(4, 2).to_rgbe();
+        let (header, pixels) = split_header(&data, 4);
+        assert_eq!(header, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 4\n");
+        assert_eq!(pixels.len(), 4 * 2 * 4);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rgbe_shares_the_exponent_of_the_brightest_channel() {
+        assert_eq!(rgbe(Tuple::color(1.0, 0.5, 0.25)), [128, 64, 32, 129]);
+        assert_eq!(rgbe(Tuple::color(12.0, 0.0, 3.0)), [192, 0, 48, 132]);
+        assert_eq!(rgbe(Tuple::color(0.0, 0.0, 0.0)), [0, 0, 0, 0]);
+        assert_eq!(rgbe(Tuple::color(-1.0, 0.5, 0.0)), [0, 128, 0, 128]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rgbe_saturates_values_too_bright_to_represent() {
+        let brightest = [255, 255, 255, 255];
+        assert_eq!(rgbe(Tuple::color(f32::MAX, f32::MAX, f32::MAX)), brightest);
+        assert_eq!(
+            rgbe(Tuple::color(f32::INFINITY, 0.0, 1.0)),
+            [255, 0, 0, 255]
+        );
+        let huge = 2.0_f32.powi(127);
+        assert_eq!(rgbe(Tuple::color(huge, huge, huge)), brightest);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rgbe_keeps_values_brighter_than_white() {
+        let mut c = Canvas::new(1, 1);
+        c.write_pixel(0, 0, Tuple::color(1000.0, 1000.0, 1000.0));
+        let data = c.to_rgbe();
+        let pixel = &data[data.len() - 4..];
+        let decoded = pixel[0] as f32 * ((pixel[3] as i32 - 128 - 8) as f32).exp2();
+        assert!((decoded - 1000.0).abs() / 1000.0 < 0.01);
+    }
+}
@@ -1,2 +1,24 @@
 export { Scene } from './scene.js'
-export { Transform, renderFromBuffer } from '@limulus/penumbra/wasm/simd'
+export {
+  Transform,
+  Projection,
+  RenderPass,
+  Integrator,
+  ShadingModel,
+  ToneMapper,
+  ImageFormat,
+  Pick,
+  PreviewMode,
+  PreviewRenderer,
+  ProgressiveRenderer,
+  SceneRenderer,
+  renderFromBuffer,
+  renderFromBufferInto,
+  renderWithIntegratorFromBuffer,
+  renderEncodedFromBuffer,
+  renderPassFromBuffer,
+  pickFromBuffer,
+  renderRegionFromBuffer,
+  getFrameTiles,
+  renderStereoFromBuffer,
+} from '@limulus/penumbra/wasm/simd'
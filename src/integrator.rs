@@ -0,0 +1,283 @@
+//! Strategies for estimating the color seen along a camera ray.
+//!
+//! The Whitted integrator is the classic Ray Tracer Challenge shader: direct light
+//! from the point light plus a constant ambient term. The path tracer replaces the
+//! ambient term with light that actually bounces between surfaces, which gives color
+//! bleeding and soft indirect light at the cost of noise that only averages away over
+//! many samples per pixel.
+
+use std::f32::consts::PI;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::ray::Ray;
+use crate::rng::Rng;
+use crate::tuple::Tuple;
+use crate::world::World;
+
+/// How a render computes the color along each ray.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Integrator {
+    /// Direct lighting with an ambient term. Deterministic, so one sample per pixel
+    /// is enough apart from antialiasing.
+    #[default]
+    Whitted = 0,
+    /// Unidirectional Monte Carlo path tracing. Needs many samples per pixel.
+    PathTracing = 1,
+}
+
+impl Integrator {
+    /// Estimates the color seen along `ray`. The Whitted integrator ignores `rng`.
+    pub fn color_at(self, world: &World, ray: Ray, rng: &mut Rng) -> Tuple {
+        match self {
+            Integrator::Whitted => world.color_at(ray),
+            Integrator::PathTracing => PathTracer::default().radiance(world, ray, rng),
+        }
+    }
+}
+
+/// Unidirectional path tracer with next-event estimation.
+///
+/// Surfaces scatter diffusely, weighted by their color and `diffuse` coefficient. At
+/// every bounce the point light is sampled directly with the material’s diffuse and
+/// specular terms, which treat it without distance falloff just like the Whitted
+/// shader, so scenes look alike under both integrators apart from the indirect light.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathTracer {
+    /// Hard limit on the number of surfaces a path may hit.
+    pub max_depth: usize,
+    /// Number of bounces before paths start being terminated by Russian roulette.
+    pub roulette_depth: usize,
+}
+
+impl Default for PathTracer {
+    fn default() -> Self {
+        PathTracer {
+            max_depth: 16,
+            roulette_depth: 3,
+        }
+    }
+}
+
+impl PathTracer {
+    /// Returns an unbiased estimate of the light arriving along `ray`, up to the depth
+    /// limit.
+    pub fn radiance(&self, world: &World, ray: Ray, rng: &mut Rng) -> Tuple {
+        let mut radiance = Tuple::color(0.0, 0.0, 0.0);
+        let mut throughput = Tuple::color(1.0, 1.0, 1.0);
+        let mut ray = ray;
+
+        for depth in 0..self.max_depth {
+            let intersections = world.intersect(ray);
+            let Some(hit) = intersections.hit() else {
+                break;
+            };
+            let comps = hit.prepare_computations(ray);
+            let material = &comps.object.material;
+
+            // Emissive surfaces are not sampled as lights, so their light is only
+            // picked up when a path happens to hit them
+            radiance = radiance + throughput * material.emission;
+
+            // Next-event estimation: light reaching this point straight from the light
+            if let Some(light) = world.light {
+                let direct = material.direct_lighting(
+                    light,
+                    comps.point,
+                    comps.eyev,
+                    comps.normalv,
+                    world.is_shadowed(comps.over_point),
+                );
+                radiance = radiance + throughput * direct;
+            }
+
+            // Cosine-weighted sampling cancels the Lambertian cosine and 1/π terms, so
+            // each bounce only scales the path by the surface albedo
+            throughput = throughput * material.color * material.diffuse;
+
+            if depth >= self.roulette_depth {
+                let survival = throughput.x().max(throughput.y()).max(throughput.z());
+                let survival = survival.min(0.95);
+                if rng.next_f32() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+
+            let direction = cosine_sample_hemisphere(comps.normalv, rng);
+            ray = Ray::new(comps.over_point, direction);
+        }
+
+        radiance
+    }
+}
+
+/// Picks a direction in the hemisphere around `normal` with probability proportional
+/// to the cosine of its angle from the normal.
+pub fn cosine_sample_hemisphere(normal: Tuple, rng: &mut Rng) -> Tuple {
+    let r = rng.next_f32().sqrt();
+    let phi = 2.0 * PI * rng.next_f32();
+    let (local_x, local_y) = (r * phi.cos(), r * phi.sin());
+    let local_z = (1.0 - r * r).max(0.0).sqrt();
+
+    // Orthonormal basis around the normal, avoiding a helper nearly parallel to it
+    let helper = if normal.x().abs() > 0.9 {
+        Tuple::vector(0.0, 1.0, 0.0)
+    } else {
+        Tuple::vector(1.0, 0.0, 0.0)
+    };
+    let tangent = helper.cross(normal).normalize();
+    let bitangent = normal.cross(tangent);
+
+    (tangent * local_x + bitangent * local_y + normal * local_z).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy::fuzzy_eq_f32;
+    use crate::light::Light;
+    use crate::material::Material;
+    use crate::sphere::Sphere;
+    use crate::transform::Transform;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cosine_samples_are_unit_vectors_in_the_normal_hemisphere() {
+        let mut rng = Rng::new(7);
+        let normal = Tuple::vector(1.0, 2.0, -3.0).normalize();
+        for _ in 0..1000 {
+            let d = cosine_sample_hemisphere(normal, &mut rng);
+            assert!(d.is_vector());
+            assert!(fuzzy_eq_f32(d.magnitude(), 1.0));
+            assert!(d.dot(normal) >= 0.0);
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cosine_samples_average_to_two_thirds_along_the_normal() {
+        // E[cos θ] under a cosine-weighted distribution is 2/3
+        let mut rng = Rng::new(11);
+        let normal = Tuple::vector(0.0, 1.0, 0.0);
+        let n = 20_000;
+        let mean: f32 = (0..n)
+            .map(|_| cosine_sample_hemisphere(normal, &mut rng).dot(normal))
+            .sum::<f32>()
+            / n as f32;
+        assert!((mean - 2.0 / 3.0).abs() < 0.01, "mean was {mean}");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn the_whitted_integrator_matches_world_color_at() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut rng = Rng::new(1);
+        let c = Integrator::Whitted.color_at(&w, r, &mut rng);
+        assert_eq!(c.as_array(), w.color_at(r).as_array());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn a_path_that_misses_everything_is_black() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+        let mut rng = Rng::new(1);
+        let c = Integrator::PathTracing.color_at(&w, r, &mut rng);
+        assert_eq!(c, Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    fn floor_and_ball(ball_color: Tuple) -> World {
+        let mut floor = Sphere::new();
+        floor
+            .set_transform(Transform::new().scale(10.0, 0.01, 10.0))
+            .unwrap();
+        floor.material = Material {
+            color: Tuple::color(1.0, 1.0, 1.0),
+            specular: 0.0,
+            ..Default::default()
+        };
+        let mut ball = Sphere::new();
+        ball.set_transform(Transform::new().translate(0.0, 1.0, 0.0))
+            .unwrap();
+        ball.material = Material {
+            color: ball_color,
+            specular: 0.0,
+            ..Default::default()
+        };
+        let mut w = World::new();
+        w.objects = vec![floor, ball];
+        w.light = Some(Light::new(
+            Tuple::point(0.0, 10.0, 0.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        w
+    }
+
+    fn mean_radiance(w: &World, r: Ray, samples: usize) -> Tuple {
+        let tracer = PathTracer::default();
+        let mut rng = Rng::new(3);
+        let mut sum = [0.0; 3];
+        for _ in 0..samples {
+            let c = tracer.radiance(w, r, &mut rng);
+            sum = [sum[0] + c.x(), sum[1] + c.y(), sum[2] + c.z()];
+        }
+        let [r, g, b] = sum.map(|channel| channel / samples as f32);
+        Tuple::color(r, g, b)
+    }
+
+    fn ray_towards(from: Tuple, to: Tuple) -> Ray {
+        Ray::new(from, (to - from).normalize())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn shadowed_surfaces_receive_indirect_light() {
+        // This part of the floor is in the ball’s shadow, so only light bouncing off
+        // the lit parts of the floor and ball reaches it
+        let w = floor_and_ball(Tuple::color(1.0, 1.0, 1.0));
+        let target = Tuple::point(0.0, 0.01, -0.3);
+        assert!(w.is_shadowed(target + Tuple::vector(0.0, 0.001, 0.0)));
+        let r = ray_towards(Tuple::point(0.0, 0.5, -3.0), target);
+        let c = mean_radiance(&w, r, 64);
+        assert!(c.x() > 0.0 && c.y() > 0.0 && c.z() > 0.0);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn indirect_light_picks_up_the_color_of_nearby_surfaces() {
+        let w = floor_and_ball(Tuple::color(1.0, 0.0, 0.0));
+        let r = ray_towards(Tuple::point(1.2, 3.0, -3.0), Tuple::point(1.2, 0.0, 0.0));
+        let c = mean_radiance(&w, r, 256);
+        assert!(
+            c.x() > c.y(),
+            "floor beside a red ball should look reddish: {c:?}"
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn emissive_surfaces_light_their_surroundings() {
+        // With the point light switched off, only the glowing ball lights the floor
+        let mut w = floor_and_ball(Tuple::color(1.0, 1.0, 1.0));
+        w.light = Some(Light::new(
+            Tuple::point(0.0, 10.0, 0.0),
+            Tuple::color(0.0, 0.0, 0.0),
+        ));
+        let r = ray_towards(Tuple::point(1.5, 3.0, -3.0), Tuple::point(1.5, 0.0, 0.0));
+        assert_eq!(mean_radiance(&w, r, 64), Tuple::color(0.0, 0.0, 0.0));
+
+        w.objects[1].material.emission = Tuple::color(0.0, 4.0, 0.0);
+        let c = mean_radiance(&w, r, 64);
+        assert!(
+            c.y() > 0.0 && c.x() == 0.0,
+            "floor should glow green: {c:?}"
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn path_tracing_is_reproducible_for_a_seed() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let a = Integrator::PathTracing.color_at(&w, r, &mut Rng::new(5));
+        let b = Integrator::PathTracing.color_at(&w, r, &mut Rng::new(5));
+        assert_eq!(a.as_array(), b.as_array());
+    }
+}
@@ -10,11 +10,18 @@ const SHADOW_SURFACE_OFFSET: f32 = 0.005;
 pub struct Intersection<'a> {
     pub t: f32,
     pub object: &'a Sphere,
+    /// Position of `object` in the world's objects, or `None` if the intersection did
+    /// not come from `World::intersect`.
+    pub object_index: Option<usize>,
 }
 
 impl<'a> Intersection<'a> {
     pub fn new(t: f32, object: &'a Sphere) -> Intersection<'a> {
-        Intersection { t, object }
+        Intersection {
+            t,
+            object,
+            object_index: None,
+        }
     }
 
     pub fn prepare_computations(&self, r: Ray) -> IntersectionComputations {
@@ -99,7 +106,7 @@ mod tests {
     use crate::transform::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn an_intersection_encapsulates_t_and_object() {
         let s = Sphere::new();
         let i = Intersection::new(3.5, &s);
@@ -108,7 +115,7 @@ mod tests {
         assert_eq!(i.object, &s);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn aggregating_intersections() {
         let s = Sphere::new();
         let i1 = Intersection::new(1.0, &s);
@@ -121,7 +128,7 @@ mod tests {
         assert_eq!(xs[1].t, 2.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn intersect_sets_the_object_on_the_intersection() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -133,7 +140,7 @@ mod tests {
         assert_eq!(xs[1].object, &s);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_when_all_intersections_have_positive_t() {
         let s = Sphere::new();
         let i1 = Intersection::new(1.0, &s);
@@ -145,7 +152,7 @@ mod tests {
         assert_eq!(i, Some(&i1));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_when_some_intersections_have_negative_t() {
         let s = Sphere::new();
         let i1 = Intersection::new(-1.0, &s);
@@ -157,7 +164,7 @@ mod tests {
         assert_eq!(i, Some(&i2));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_when_all_intersections_have_negative_t() {
         let s = Sphere::new();
         let i1 = Intersection::new(-2.0, &s);
@@ -169,7 +176,7 @@ mod tests {
         assert_eq!(i, None);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_is_always_the_lowest_nonnegative_intersection() {
         let s = Sphere::new();
         let i1 = Intersection::new(5.0, &s);
@@ -183,7 +190,7 @@ mod tests {
         assert_eq!(i, Some(&i4));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn precomputing_the_state_of_an_intersection() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let shape = Sphere::default();
@@ -198,7 +205,7 @@ mod tests {
         assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_when_an_intersection_occurs_on_the_outside() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let shape = Sphere::default();
@@ -209,7 +216,7 @@ mod tests {
         assert_eq!(comps.inside, false);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_hit_when_an_intersection_occurs_on_the_inside() {
         let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
         let shape = Sphere::default();
@@ -223,7 +230,7 @@ mod tests {
         assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_hit_should_offset_the_point() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let mut shape = Sphere::default();
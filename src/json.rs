@@ -0,0 +1,428 @@
+//! Saving and loading scenes as JSON.
+//!
+//! A `SceneDescription` is plain data: the camera placement, lights, tone mapping and
+//! objects with their transforms and materials. Image size and field of view are left
+//! to the renderer, as with scene buffers. Every document carries a schema `version`
+//! so that files saved today keep loading, or fail clearly, once the format grows.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "camera": { "from": [0, 1.5, -5], "to": [0, 1, 0], "up": [0, 1, 0] },
+//!   "lights": [{ "position": [-10, 10, -10], "intensity": [1, 1, 1] }],
+//!   "objects": [
+//!     {
+//!       "type": "sphere",
+//!       "transform": [[1, 0, 0, 0], [0, 1, 0, 1], [0, 0, 1, 0], [0, 0, 0, 1]],
+//!       "material": { "color": [1, 0.2, 0.1], "specular": 0.3 }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Omitted material properties, tone mapping and projection take their defaults.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{Camera, Projection};
+use crate::light::Light;
+use crate::material::{Material, ShadingModel};
+use crate::matrix::Matrix4;
+use crate::sphere::Sphere;
+use crate::tonemap::ToneMapping;
+use crate::transform::{view_transform, Transform};
+use crate::tuple::Tuple;
+use crate::world::World;
+
+/// The schema version written by `to_json`, and the newest one `from_json` reads.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Why a JSON scene could not be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonError {
+    /// The text is not JSON, or does not match the scene schema.
+    Syntax(String),
+    /// The document's version is 0 or newer than this build reads.
+    UnsupportedVersion(u32),
+    /// The scene has more lights than the renderer supports.
+    TooManyLights(usize),
+    /// The camera’s up vector is parallel to its view direction.
+    InvalidCamera,
+    /// The transform of the object at this index cannot be inverted.
+    SingularTransform(usize),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax(message) => write!(f, "invalid scene JSON: {message}"),
+            JsonError::UnsupportedVersion(version) => write!(
+                f,
+                "scene version {version} is not supported; expected 1 to {SCHEMA_VERSION}"
+            ),
+            JsonError::TooManyLights(count) => {
+                write!(f, "scene has {count} lights, but only one is supported")
+            }
+            JsonError::InvalidCamera => {
+                write!(f, "camera up vector is parallel to its view direction")
+            }
+            JsonError::SingularTransform(index) => {
+                write!(f, "transform of object {index} is not invertible")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// A complete scene in its serializable form.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneDescription {
+    pub version: u32,
+    pub camera: CameraDescription,
+    #[serde(default)]
+    pub lights: Vec<LightDescription>,
+    #[serde(default)]
+    pub tone_mapping: ToneMapping,
+    #[serde(default)]
+    pub objects: Vec<ObjectDescription>,
+}
+
+/// Where the camera is and where it looks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraDescription {
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    pub up: [f32; 3],
+    #[serde(default)]
+    pub projection: Projection,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LightDescription {
+    pub position: [f32; 3],
+    pub intensity: [f32; 3],
+}
+
+/// An object in the scene, tagged by its `type`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ObjectDescription {
+    Sphere {
+        /// Row-major 4×4 object-to-world matrix.
+        #[serde(default = "identity_rows")]
+        transform: [[f32; 4]; 4],
+        #[serde(default)]
+        material: MaterialDescription,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MaterialDescription {
+    pub color: [f32; 3],
+    pub ambient: f32,
+    pub diffuse: f32,
+    pub specular: f32,
+    pub shininess: f32,
+    pub emission: [f32; 3],
+    pub shading_model: ShadingModel,
+    pub roughness: f32,
+    pub metalness: f32,
+}
+
+impl Default for MaterialDescription {
+    fn default() -> Self {
+        MaterialDescription::from(&Material::default())
+    }
+}
+
+impl From<&Material> for MaterialDescription {
+    fn from(material: &Material) -> Self {
+        MaterialDescription {
+            color: rgb(material.color),
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            shininess: material.shininess,
+            emission: rgb(material.emission),
+            shading_model: material.shading_model,
+            roughness: material.roughness,
+            metalness: material.metalness,
+        }
+    }
+}
+
+impl From<&MaterialDescription> for Material {
+    fn from(material: &MaterialDescription) -> Self {
+        let [r, g, b] = material.color;
+        let [er, eg, eb] = material.emission;
+        Material {
+            color: Tuple::color(r, g, b),
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            shininess: material.shininess,
+            emission: Tuple::color(er, eg, eb),
+            shading_model: material.shading_model,
+            roughness: material.roughness,
+            metalness: material.metalness,
+        }
+    }
+}
+
+impl SceneDescription {
+    /// Parses a scene saved by `to_json`, accepting any schema version from 1 up to
+    /// the current one.
+    pub fn from_json(json: &str) -> Result<SceneDescription, JsonError> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+
+        // Check the version first, so an unsupported document is reported as such
+        // rather than as whatever field it fails to parse
+        let Versioned { version } =
+            serde_json::from_str(json).map_err(|e| JsonError::Syntax(e.to_string()))?;
+        if !(1..=SCHEMA_VERSION).contains(&version) {
+            return Err(JsonError::UnsupportedVersion(version));
+        }
+        serde_json::from_str(json).map_err(|e| JsonError::Syntax(e.to_string()))
+    }
+
+    /// Serializes the scene as indented JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scene descriptions always serialize")
+    }
+
+    /// Builds a camera and world that render the scene at the given size.
+    pub fn build(
+        &self,
+        width: usize,
+        height: usize,
+        fov: f32,
+    ) -> Result<(Camera, World, ToneMapping), JsonError> {
+        let CameraDescription {
+            from,
+            to,
+            up,
+            projection,
+        } = self.camera;
+        let mut camera = Camera::new(width, height, fov);
+        camera
+            .set_transform(view_transform(point(from), point(to), vector(up)))
+            .map_err(|_| JsonError::InvalidCamera)?;
+        camera.set_projection(projection);
+
+        let mut world = World::new();
+        world.light = match self.lights.as_slice() {
+            // Shading needs a light, so an unlit scene gets one that gives off nothing,
+            // just like a scene buffer whose light was never set
+            [] => Some(Light::new(
+                Tuple::point(0.0, 0.0, 0.0),
+                Tuple::color(0.0, 0.0, 0.0),
+            )),
+            [light] => Some(Light::new(point(light.position), color(light.intensity))),
+            lights => return Err(JsonError::TooManyLights(lights.len())),
+        };
+        for (index, object) in self.objects.iter().enumerate() {
+            let ObjectDescription::Sphere {
+                transform,
+                material,
+            } = object;
+            let mut sphere = Sphere::new();
+            sphere
+                .set_transform(Transform::from_matrix(matrix_from_rows(transform)))
+                .map_err(|_| JsonError::SingularTransform(index))?;
+            sphere.material = Material::from(material);
+            world.objects.push(sphere);
+        }
+
+        Ok((camera, world, self.tone_mapping))
+    }
+}
+
+fn identity_rows() -> [[f32; 4]; 4] {
+    rows_from_matrix(&Matrix4::identity())
+}
+
+pub(crate) fn rows_from_matrix(matrix: &Matrix4) -> [[f32; 4]; 4] {
+    std::array::from_fn(|row| std::array::from_fn(|col| matrix.get(row, col)))
+}
+
+pub(crate) fn matrix_from_rows(rows: &[[f32; 4]; 4]) -> Matrix4 {
+    let [a, b, c, d] = rows;
+    Matrix4::new(
+        a[0], a[1], a[2], a[3], b[0], b[1], b[2], b[3], c[0], c[1], c[2], c[3], d[0], d[1],
+        d[2], d[3],
+    )
+}
+
+pub(crate) fn rgb(color: Tuple) -> [f32; 3] {
+    [color.x(), color.y(), color.z()]
+}
+
+fn point([x, y, z]: [f32; 3]) -> Tuple {
+    Tuple::point(x, y, z)
+}
+
+fn vector([x, y, z]: [f32; 3]) -> Tuple {
+    Tuple::vector(x, y, z)
+}
+
+fn color([r, g, b]: [f32; 3]) -> Tuple {
+    Tuple::color(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tonemap::ToneMapper;
+    use wasm_bindgen_test::*;
+
+    fn sample() -> SceneDescription {
+        SceneDescription {
+            version: SCHEMA_VERSION,
+            camera: CameraDescription {
+                from: [0.0, 1.5, -5.0],
+                to: [0.0, 1.0, 0.0],
+                up: [0.0, 1.0, 0.0],
+                projection: Projection::Equirectangular,
+            },
+            lights: vec![LightDescription {
+                position: [-10.0, 10.0, -10.0],
+                intensity: [1.0, 0.9, 0.8],
+            }],
+            tone_mapping: ToneMapping::new(ToneMapper::Aces, 0.5, true),
+            objects: vec![ObjectDescription::Sphere {
+                transform: rows_from_matrix(
+                    &(Matrix4::translation(1.0, 2.0, 3.0)
+                        * Matrix4::scaling(0.5, 0.5, 0.5)),
+                ),
+                material: MaterialDescription {
+                    color: [1.0, 0.2, 0.1],
+                    emission: [0.0, 0.5, 0.0],
+                    shading_model: ShadingModel::CookTorrance,
+                    roughness: 0.3,
+                    ..Default::default()
+                },
+            }],
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn descriptions_round_trip_through_json() {
+        let scene = sample();
+        let json = scene.to_json();
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"type\": \"sphere\""));
+        assert!(json.contains("\"shadingModel\": \"cookTorrance\""));
+        assert_eq!(SceneDescription::from_json(&json), Ok(scene));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn omitted_fields_take_their_defaults() {
+        let scene = SceneDescription::from_json(
+            r#"{
+                "version": 1,
+                "camera": { "from": [0, 0, -5], "to": [0, 0, 0], "up": [0, 1, 0] },
+                "objects": [{ "type": "sphere", "material": { "color": [1, 0, 0] } }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(scene.camera.projection, Projection::Perspective);
+        assert!(scene.lights.is_empty());
+        assert_eq!(scene.tone_mapping, ToneMapping::default());
+        let ObjectDescription::Sphere {
+            transform,
+            material,
+        } = &scene.objects[0];
+        assert_eq!(matrix_from_rows(transform), Matrix4::identity());
+        assert_eq!(material.color, [1.0, 0.0, 0.0]);
+        assert_eq!(material.diffuse, Material::default().diffuse);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn building_a_description_creates_its_camera_and_world() {
+        let (camera, world, tone_mapping) = sample().build(40, 20, 1.0).unwrap();
+        assert_eq!(camera.size(), (40, 20));
+        assert_eq!(camera.projection(), Projection::Equirectangular);
+        assert_eq!(
+            camera.transform(),
+            view_transform(
+                Tuple::point(0.0, 1.5, -5.0),
+                Tuple::point(0.0, 1.0, 0.0),
+                Tuple::vector(0.0, 1.0, 0.0)
+            )
+        );
+        assert_eq!(
+            world.light,
+            Some(Light::new(
+                Tuple::point(-10.0, 10.0, -10.0),
+                Tuple::color(1.0, 0.9, 0.8)
+            ))
+        );
+        let sphere = &world.objects[0];
+        assert_eq!(
+            sphere.transform(),
+            Matrix4::translation(1.0, 2.0, 3.0) * Matrix4::scaling(0.5, 0.5, 0.5)
+        );
+        assert_eq!(sphere.material.shading_model, ShadingModel::CookTorrance);
+        assert!(sphere.material.emission.rgb_eq(Tuple::color(0.0, 0.5, 0.0)));
+        assert_eq!(tone_mapping, ToneMapping::new(ToneMapper::Aces, 0.5, true));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn newer_versions_are_rejected_before_parsing_the_rest() {
+        let err = SceneDescription::from_json(r#"{ "version": 2, "camera": "elsewhere" }"#)
+            .err()
+            .unwrap();
+        assert_eq!(err, JsonError::UnsupportedVersion(2));
+        assert!(matches!(
+            SceneDescription::from_json(r#"{ "camera": {} }"#),
+            Err(JsonError::Syntax(_))
+        ));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn version_zero_is_rejected() {
+        let mut json = sample().to_json();
+        json = json.replacen(r#""version": 1"#, r#""version": 0"#, 1);
+        assert_eq!(
+            SceneDescription::from_json(&json).err(),
+            Some(JsonError::UnsupportedVersion(0))
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn invalid_scenes_fail_to_build() {
+        let mut scene = sample();
+        scene.lights.push(scene.lights[0].clone());
+        assert_eq!(
+            scene.build(10, 10, 1.0).err(),
+            Some(JsonError::TooManyLights(2))
+        );
+
+        let mut scene = sample();
+        scene.objects.push(ObjectDescription::Sphere {
+            transform: rows_from_matrix(&Matrix4::scaling(0.0, 1.0, 1.0)),
+            material: MaterialDescription::default(),
+        });
+        assert_eq!(
+            scene.build(10, 10, 1.0).err(),
+            Some(JsonError::SingularTransform(1))
+        );
+
+        let mut scene = sample();
+        scene.camera.up = [0.0, -0.5, 5.0];
+        assert_eq!(
+            scene.build(10, 10, 1.0).err(),
+            Some(JsonError::InvalidCamera)
+        );
+    }
+}
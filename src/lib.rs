@@ -1,26 +1,44 @@
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "web")]
 pub mod bench;
 pub mod camera;
 pub mod canvas;
 pub mod fuzzy;
+pub mod hdr;
+pub mod integrator;
 pub mod intersection;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod light;
 pub mod material;
 pub mod matrix;
+pub mod png;
+pub mod ppm;
+pub mod progressive;
 pub mod ray;
+pub mod rng;
 pub mod scene;
 pub mod sphere;
+pub mod threads;
+pub mod tonemap;
 pub mod transform;
 pub mod tuple;
 pub mod world;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
+#[cfg(feature = "web")]
 mod demo;
+#[cfg(feature = "web")]
 pub use demo::phong_sphere::*;
+#[cfg(feature = "web")]
 pub use demo::sphere_shadow::*;
 pub use scene::*;
 pub use transform::Transform;
 
+#[cfg(feature = "web")]
 #[wasm_bindgen(start)]
 pub fn run() -> Result<(), JsValue> {
     #[cfg(feature = "console_error_panic_hook")]
@@ -26,7 +26,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_point_light_has_a_position_and_intensity() {
         let intensity = Tuple::color(1.0, 1.0, 1.0);
         let position = Tuple::point(0.0, 0.0, 0.0);
@@ -35,7 +35,7 @@ mod tests {
         assert_eq!(light.position, position);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lights_are_equal_if_they_have_the_same_position_and_intensity() {
         let intensity = Tuple::color(1.0, 1.0, 1.0);
         let position = Tuple::point(0.0, 0.0, 0.0);
@@ -1,7 +1,32 @@
+use std::f32::consts::PI;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
 use crate::fuzzy::*;
 use crate::light::*;
 use crate::tuple::*;
 
+/// How a material reflects direct light into its specular highlight.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum ShadingModel {
+    /// Classic Phong: highlight from the angle between the eye and the reflected light.
+    #[default]
+    Phong = 0,
+    /// Blinn-Phong: highlight from the angle between the normal and the half vector.
+    /// Highlights are wider than Phong’s for the same shininess.
+    BlinnPhong = 1,
+    /// Cook-Torrance microfacet model with the GGX distribution, driven by `roughness`
+    /// and `metalness` instead of `specular` and `shininess`.
+    CookTorrance = 2,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Material {
     pub color: Tuple,
@@ -9,6 +34,13 @@ pub struct Material {
     pub diffuse: f32,
     pub specular: f32,
     pub shininess: f32,
+    /// Light given off by the surface itself, independent of any light source.
+    pub emission: Tuple,
+    pub shading_model: ShadingModel,
+    /// Microfacet roughness from 0 (mirror-like) to 1, used by Cook-Torrance.
+    pub roughness: f32,
+    /// 0 for dielectrics, 1 for metals, used by Cook-Torrance.
+    pub metalness: f32,
 }
 
 impl Default for Material {
@@ -19,6 +51,10 @@ impl Default for Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            emission: Tuple::color(0.0, 0.0, 0.0),
+            shading_model: ShadingModel::Phong,
+            roughness: 0.5,
+            metalness: 0.0,
         }
     }
 }
@@ -30,6 +66,10 @@ impl PartialEq<Material> for Material {
             && fuzzy_eq_f32(self.diffuse, other.diffuse)
             && fuzzy_eq_f32(self.specular, other.specular)
             && fuzzy_eq_f32(self.shininess, other.shininess)
+            && self.emission == other.emission
+            && self.shading_model == other.shading_model
+            && fuzzy_eq_f32(self.roughness, other.roughness)
+            && fuzzy_eq_f32(self.metalness, other.metalness)
     }
 }
 
@@ -41,6 +81,24 @@ impl Material {
         eyev: Tuple,
         normalv: Tuple,
         in_shadow: bool,
+    ) -> Tuple {
+        // Ambient light contribution
+        let ambient = self.color * light.intensity * self.ambient;
+
+        self.emission
+            + ambient
+            + self.direct_lighting(light, point, eyev, normalv, in_shadow)
+    }
+
+    /// The diffuse and specular light reflected straight from `light`, without the
+    /// ambient term that stands in for indirect light.
+    pub fn direct_lighting(
+        &self,
+        light: Light,
+        point: Tuple,
+        eyev: Tuple,
+        normalv: Tuple,
+        in_shadow: bool,
     ) -> Tuple {
         // Combined color of this material and the light’s color/intensity
         let effective_color = self.color * light.intensity;
@@ -48,31 +106,79 @@ impl Material {
         // Direction to the light source
         let lightv = (light.position - point).normalize();
 
-        // Ambient light contribution
-        let ambient = effective_color * self.ambient;
-
         let cos_between_lightv_and_normalv = lightv.dot(normalv);
         let light_is_behind_surface = cos_between_lightv_and_normalv < 0.0;
-        let (diffuse, specular) = if light_is_behind_surface || in_shadow {
-            (Tuple::color(0.0, 0.0, 0.0), Tuple::color(0.0, 0.0, 0.0))
+        if light_is_behind_surface || in_shadow {
+            return Tuple::color(0.0, 0.0, 0.0);
+        }
+
+        if self.shading_model == ShadingModel::CookTorrance {
+            return self.cook_torrance(light, lightv, eyev, normalv);
+        }
+
+        // Diffuse contribution
+        let diffuse = effective_color * self.diffuse * cos_between_lightv_and_normalv;
+        let light_reflects_away_from_eye = cos_between_lightv_and_normalv <= 0.0;
+
+        let specular = if light_reflects_away_from_eye {
+            Tuple::color(0.0, 0.0, 0.0)
         } else {
-            // Diffuse contribution
-            let diffuse = effective_color * self.diffuse * cos_between_lightv_and_normalv;
-            let reflectv = (-lightv).reflect(normalv);
-            let cos_between_reflectv_and_eyev = reflectv.dot(eyev);
-            let light_reflects_away_from_eye = cos_between_lightv_and_normalv <= 0.0;
-
-            let specular = if light_reflects_away_from_eye {
-                Tuple::color(0.0, 0.0, 0.0)
-            } else {
-                let factor = cos_between_reflectv_and_eyev.powf(self.shininess);
-                light.intensity * self.specular * factor
+            let cos = match self.shading_model {
+                ShadingModel::BlinnPhong => (lightv + eyev).normalize().dot(normalv),
+                _ => (-lightv).reflect(normalv).dot(eyev),
             };
-
-            (diffuse, specular)
+            let factor = cos.powf(self.shininess);
+            light.intensity * self.specular * factor
         };
 
-        ambient + diffuse + specular
+        diffuse + specular
+    }
+
+    /// Direct light under the Cook-Torrance model with GGX normal distribution, Smith
+    /// geometry term and Schlick’s Fresnel approximation.
+    ///
+    /// Like the Phong terms, the light is treated as arriving without falloff and the
+    /// Lambertian 1/π is folded into its intensity, so a rough dielectric looks about
+    /// as bright as the same color under Phong.
+    fn cook_torrance(
+        &self,
+        light: Light,
+        lightv: Tuple,
+        eyev: Tuple,
+        normalv: Tuple,
+    ) -> Tuple {
+        let n_dot_l = normalv.dot(lightv);
+        // Grazing light contributes nothing, and would divide zero by zero below
+        if n_dot_l <= 0.0 {
+            return Tuple::color(0.0, 0.0, 0.0);
+        }
+        let n_dot_v = normalv.dot(eyev).max(1e-4);
+        let halfv = (lightv + eyev).normalize();
+        let n_dot_h = normalv.dot(halfv).max(0.0);
+        let v_dot_h = eyev.dot(halfv).max(0.0);
+
+        // GGX normal distribution with the usual α = roughness² remapping
+        let alpha = (self.roughness * self.roughness).max(1e-3);
+        let alpha2 = alpha * alpha;
+        let denom = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+        let distribution = alpha2 / (PI * denom * denom);
+
+        // Smith geometry term with Schlick-GGX for direct lighting
+        let k = (self.roughness + 1.0) * (self.roughness + 1.0) / 8.0;
+        let g1 = |cos: f32| cos / (cos * (1.0 - k) + k);
+        let geometry = g1(n_dot_l) * g1(n_dot_v);
+
+        // Schlick’s Fresnel: dielectrics reflect 4% head-on, metals their own color
+        let dielectric = Tuple::color(0.04, 0.04, 0.04);
+        let f0 = dielectric * (1.0 - self.metalness) + self.color * self.metalness;
+        let fresnel = f0 + (Tuple::color(1.0, 1.0, 1.0) - f0) * (1.0 - v_dot_h).powi(5);
+
+        let specular = fresnel * (distribution * geometry / (4.0 * n_dot_l * n_dot_v));
+        let diffuse_weight =
+            (Tuple::color(1.0, 1.0, 1.0) - fresnel) * (1.0 - self.metalness);
+        let diffuse = diffuse_weight * self.color * self.diffuse;
+
+        (diffuse + specular * PI) * light.intensity * n_dot_l
     }
 }
 
@@ -81,7 +187,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn default_material() {
         let m = Material::default();
         assert_eq!(m.color, Tuple::color(1.0, 1.0, 1.0));
@@ -89,16 +195,20 @@ mod tests {
         assert_eq!(m.diffuse, 0.9);
         assert_eq!(m.specular, 0.9);
         assert_eq!(m.shininess, 200.0);
+        assert_eq!(m.emission, Tuple::color(0.0, 0.0, 0.0));
+        assert_eq!(m.shading_model, ShadingModel::Phong);
+        assert_eq!(m.roughness, 0.5);
+        assert_eq!(m.metalness, 0.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn materials_are_equal() {
         let m1 = Material::default();
         let m2 = Material::default();
         assert_eq!(m1, m2);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn materials_are_not_equal() {
         let m1 = Material::default();
         let mut m2 = Material::default();
@@ -106,7 +216,7 @@ mod tests {
         assert_ne!(m1, m2);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn materials_with_fuzzy_equal_properties_are_equal() {
         let m1 = Material::default();
         let mut m2 = Material::default();
@@ -118,7 +228,7 @@ mod tests {
         (Material::default(), Tuple::point(0.0, 0.0, 0.0))
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_the_eye_between_light_and_surface() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, 0.0, -1.0);
@@ -128,7 +238,7 @@ mod tests {
         assert!(result.rgb_eq(Tuple::color(1.9, 1.9, 1.9)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_eye_offset_45_degrees() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, 2f32.sqrt() / 2.0, -2f32.sqrt() / 2.0);
@@ -138,7 +248,7 @@ mod tests {
         assert!(result.rgb_eq(Tuple::color(1.0, 1.0, 1.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_light_offset_45_degrees() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, 0.0, -1.0);
@@ -148,7 +258,7 @@ mod tests {
         assert!(result.rgb_eq(Tuple::color(0.7364, 0.7364, 0.7364)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_eye_in_path_of_reflection_vector() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, -2f32.sqrt() / 2.0, -2f32.sqrt() / 2.0);
@@ -158,7 +268,7 @@ mod tests {
         assert!(result.rgb_eq(Tuple::color(1.63638, 1.63638, 1.63638)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_light_behind_surface() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, 0.0, -1.0);
@@ -168,7 +278,7 @@ mod tests {
         assert!(result.rgb_eq(Tuple::color(0.1, 0.1, 0.1)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn lighting_with_surface_in_shadow() {
         let (m, position) = background();
         let eyev = Tuple::vector(0.0, 0.0, -1.0);
@@ -178,4 +288,88 @@ mod tests {
         let result = m.lighting(light, position, eyev, normalv, in_shadow);
         assert!(result.rgb_eq(Tuple::color(0.1, 0.1, 0.1)));
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn emission_is_added_even_in_shadow() {
+        let (mut m, position) = background();
+        m.emission = Tuple::color(0.5, 0.25, 0.0);
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, true);
+        assert!(result.rgb_eq(Tuple::color(0.6, 0.35, 0.1)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn blinn_phong_highlights_are_wider_than_phong() {
+        let (mut m, position) = background();
+        m.shininess = 10.0;
+        let eyev = Tuple::vector(0.0, 2f32.sqrt() / 2.0, -2f32.sqrt() / 2.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let phong = m.lighting(light, position, eyev, normalv, false);
+        m.shading_model = ShadingModel::BlinnPhong;
+        let blinn_phong = m.lighting(light, position, eyev, normalv, false);
+        assert!(blinn_phong.x() > phong.x() + 0.3);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn blinn_phong_with_the_eye_between_light_and_surface() {
+        let (mut m, position) = background();
+        m.shading_model = ShadingModel::BlinnPhong;
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, false);
+        assert!(result.rgb_eq(Tuple::color(1.9, 1.9, 1.9)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn cook_torrance_rough_dielectric_head_on() {
+        let (mut m, position) = background();
+        m.shading_model = ShadingModel::CookTorrance;
+        m.roughness = 1.0;
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, false);
+        // ambient 0.1 + diffuse 0.96 × 0.9 + specular 0.04 / 4
+        assert!(result.rgb_eq(Tuple::color(0.974, 0.974, 0.974)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn cook_torrance_metals_tint_their_highlights() {
+        let (mut m, position) = background();
+        m.color = Tuple::color(1.0, 0.0, 0.0);
+        m.shading_model = ShadingModel::CookTorrance;
+        m.metalness = 1.0;
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, false);
+        // Metals have no diffuse lobe; the GGX peak for roughness 0.5 is 4× the light
+        assert!(result.rgb_eq(Tuple::color(4.1, 0.0, 0.0)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn cook_torrance_light_at_grazing_incidence() {
+        let (mut m, position) = background();
+        m.shading_model = ShadingModel::CookTorrance;
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 10.0, 0.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, false);
+        assert!(result.rgb_eq(Tuple::color(0.1, 0.1, 0.1)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    pub fn cook_torrance_surface_in_shadow() {
+        let (mut m, position) = background();
+        m.shading_model = ShadingModel::CookTorrance;
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = Light::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = m.lighting(light, position, eyev, normalv, true);
+        assert!(result.rgb_eq(Tuple::color(0.1, 0.1, 0.1)));
+    }
 }
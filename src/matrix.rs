@@ -401,7 +401,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn constructing_and_inspecting_a_4x4_matrix() {
         let m = Matrix4::new(
             1.0, 2.0, 3.0, 4.0, 5.5, 6.5, 7.5, 8.5, 9.0, 10.0, 11.0, 12.0, 13.5, 14.5,
@@ -417,7 +417,7 @@ mod tests {
         assert_eq!(m.get(3, 2), 15.5);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_2x2_matrix_ought_to_be_representable() {
         let m = Matrix2::new(-3.0, 5.0, 1.0, -2.0);
 
@@ -427,7 +427,7 @@ mod tests {
         assert_eq!(m.get(1, 1), -2.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_3x3_matrix_ought_to_be_representable() {
         let m = Matrix3 {
             data: [-3.0, 5.0, 0.0, 1.0, -2.0, -7.0, 0.0, 1.0, 1.0],
@@ -438,7 +438,7 @@ mod tests {
         assert_eq!(m.get(2, 2), 1.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn matrix_equality_with_identical_matrices() {
         let a = Matrix4::new(
             1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
@@ -450,7 +450,7 @@ mod tests {
         assert_eq!(a, b);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn matrix_equality_with_different_matrices() {
         let a = Matrix4::new(
             1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
@@ -462,7 +462,7 @@ mod tests {
         assert_ne!(a, b);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_two_matrices() {
         let a = Matrix4::new(
             1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
@@ -479,7 +479,7 @@ mod tests {
         assert_eq!(a * b, expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_matrix_multiplied_by_a_tuple() {
         let a = Matrix4::new(
             1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 4.0, 2.0, 8.0, 6.0, 4.0, 1.0, 0.0, 0.0, 0.0, 1.0,
@@ -489,7 +489,7 @@ mod tests {
         assert_eq!(a * b, Tuple::new(18.0, 24.0, 33.0, 1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_a_matrix_by_the_identity_matrix() {
         let a = Matrix4::new(
             0.0, 1.0, 2.0, 4.0, 1.0, 2.0, 4.0, 8.0, 2.0, 4.0, 8.0, 16.0, 4.0, 8.0, 16.0,
@@ -503,14 +503,14 @@ mod tests {
         assert_eq!(a * Matrix4::identity(), expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_the_identity_matrix_by_a_tuple() {
         let a = Tuple::new(1.0, 2.0, 3.0, 4.0);
 
         assert_eq!(Matrix4::identity() * a, a);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn transposing_a_matrix() {
         let a = Matrix4::new(
             0.0, 9.0, 3.0, 0.0, 9.0, 8.0, 0.0, 8.0, 1.0, 8.0, 5.0, 3.0, 0.0, 0.0, 5.0, 8.0,
@@ -522,19 +522,19 @@ mod tests {
         assert_eq!(a.transpose(), expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn transposing_the_identity_matrix() {
         assert_eq!(Matrix4::identity().transpose(), Matrix4::identity());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_determinant_of_a_2x2_matrix() {
         let a = Matrix2::new(1.0, 5.0, -3.0, 2.0);
 
         assert_eq!(a.determinant(), 17.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_submatrix_of_a_3x3_matrix_is_a_2x2_matrix() {
         let a = Matrix3::new(1.0, 5.0, 0.0, -3.0, 2.0, 7.0, 0.0, 6.0, -3.0);
         let expected = Matrix2::new(-3.0, 2.0, 0.0, 6.0);
@@ -542,7 +542,7 @@ mod tests {
         assert_eq!(a.submatrix(0, 2), expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_submatrix_of_a_4x4_matrix_is_a_3x3_matrix() {
         let a = Matrix4::new(
             -6.0, 1.0, 1.0, 6.0, -8.0, 5.0, 8.0, 6.0, -1.0, 0.0, 8.0, 2.0, -7.0, 1.0, -1.0,
@@ -553,7 +553,7 @@ mod tests {
         assert_eq!(a.submatrix(2, 1), expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_a_minor_of_a_3x3_matrix() {
         let a = Matrix3::new(3.0, 5.0, 0.0, 2.0, -1.0, -7.0, 6.0, -1.0, 5.0);
         let b = a.submatrix(1, 0);
@@ -562,7 +562,7 @@ mod tests {
         assert_eq!(a.minor(1, 0), 25.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_a_cofactor_of_a_3x3_matrix() {
         let a = Matrix3::new(3.0, 5.0, 0.0, 2.0, -1.0, -7.0, 6.0, -1.0, 5.0);
 
@@ -572,7 +572,7 @@ mod tests {
         assert_eq!(a.cofactor(1, 0), -25.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_determinant_of_a_3x3_matrix() {
         let a = Matrix3::new(1.0, 2.0, 6.0, -5.0, 8.0, -4.0, 2.0, 6.0, 4.0);
 
@@ -582,7 +582,7 @@ mod tests {
         assert_eq!(a.determinant(), -196.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_determinant_of_a_4x4_matrix() {
         let a = Matrix4::new(
             -2.0, -8.0, 3.0, 5.0, -3.0, 1.0, 7.0, 3.0, 1.0, 2.0, -9.0, 6.0, -6.0, 7.0, 7.0,
@@ -596,7 +596,7 @@ mod tests {
         assert_eq!(a.determinant(), -4071.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn testing_an_invertible_matrix_for_invertibility() {
         let a = Matrix4::new(
             6.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 6.0, 4.0, -9.0, 3.0, -7.0, 9.0, 1.0, 7.0,
@@ -607,7 +607,7 @@ mod tests {
         assert!(a.is_invertible());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn testing_a_noninvertible_matrix_for_invertibility() {
         let a = Matrix4::new(
             -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0,
@@ -618,7 +618,7 @@ mod tests {
         assert!(!a.is_invertible());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_inverse_of_a_matrix() {
         let a = Matrix4::new(
             -5.0, 2.0, 6.0, -8.0, 1.0, -5.0, 1.0, 8.0, 7.0, 7.0, -6.0, -7.0, 1.0, -3.0,
@@ -638,7 +638,7 @@ mod tests {
         assert_eq!(b, expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_inverse_of_another_matrix() {
         let a = Matrix4::new(
             8.0, -5.0, 9.0, 2.0, 7.0, 5.0, 6.0, 1.0, -6.0, 0.0, 9.0, 6.0, -3.0, 0.0, -9.0,
@@ -653,7 +653,7 @@ mod tests {
         assert_eq!(b, expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn calculating_the_inverse_of_a_third_matrix() {
         let a = Matrix4::new(
             9.0, 3.0, 0.0, 9.0, -5.0, -2.0, -6.0, -3.0, -4.0, 9.0, 6.0, 4.0, -7.0, 6.0,
@@ -668,7 +668,7 @@ mod tests {
         assert_eq!(b, expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_a_product_by_its_inverse() {
         let a = Matrix4::new(
             3.0, -9.0, 7.0, 3.0, 3.0, -8.0, 2.0, -9.0, -4.0, 4.0, 4.0, 1.0, -6.0, 5.0,
@@ -683,7 +683,7 @@ mod tests {
         assert_eq!(c * b.inverse().unwrap(), a);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_by_a_translation_matrix() {
         let transform = Matrix4::translation(5.0, -3.0, 2.0);
         let p = Tuple::point(-3.0, 4.0, 5.0);
@@ -691,7 +691,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(2.0, 1.0, 7.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_by_the_inverse_of_a_translation_matrix() {
         let transform = Matrix4::translation(5.0, -3.0, 2.0);
         let inv = transform.inverse().unwrap();
@@ -700,7 +700,7 @@ mod tests {
         assert_eq!(inv * p, Tuple::point(-8.0, 7.0, 3.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn translation_does_not_affect_vectors() {
         let transform = Matrix4::translation(5.0, -3.0, 2.0);
         let v = Tuple::vector(-3.0, 4.0, 5.0);
@@ -708,7 +708,7 @@ mod tests {
         assert_eq!(transform * v, v);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_scaling_matrix_applied_to_a_point() {
         let transform = Matrix4::scaling(2.0, 3.0, 4.0);
         let p = Tuple::point(-4.0, 6.0, 8.0);
@@ -716,7 +716,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(-8.0, 18.0, 32.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_scaling_matrix_applied_to_a_vector() {
         let transform = Matrix4::scaling(2.0, 3.0, 4.0);
         let v = Tuple::vector(-4.0, 6.0, 8.0);
@@ -724,7 +724,7 @@ mod tests {
         assert_eq!(transform * v, Tuple::vector(-8.0, 18.0, 32.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn multiplying_by_the_inverse_of_a_scaling_matrix() {
         let transform = Matrix4::scaling(2.0, 3.0, 4.0);
         let inv = transform.inverse().unwrap();
@@ -733,7 +733,7 @@ mod tests {
         assert_eq!(inv * v, Tuple::vector(-2.0, 2.0, 2.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn reflection_is_scaling_by_a_negative_value() {
         let transform = Matrix4::scaling(-1.0, 1.0, 1.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -741,7 +741,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(-2.0, 3.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn rotating_a_point_around_the_x_axis() {
         let p = Tuple::point(0.0, 1.0, 0.0);
         let half_quarter = Matrix4::rotation_x(std::f32::consts::PI / 4.0);
@@ -754,7 +754,7 @@ mod tests {
         assert_eq!(full_quarter * p, Tuple::point(0.0, 0.0, 1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_inverse_of_an_x_rotation_rotates_in_the_opposite_direction() {
         let p = Tuple::point(0.0, 1.0, 0.0);
         let half_quarter = Matrix4::rotation_x(std::f32::consts::PI / 4.0);
@@ -766,7 +766,7 @@ mod tests {
         );
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn rotating_a_point_around_the_y_axis() {
         let p = Tuple::point(0.0, 0.0, 1.0);
         let half_quarter = Matrix4::rotation_y(std::f32::consts::PI / 4.0);
@@ -779,7 +779,7 @@ mod tests {
         assert_eq!(full_quarter * p, Tuple::point(1.0, 0.0, 0.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn rotating_a_point_around_the_z_axis() {
         let p = Tuple::point(0.0, 1.0, 0.0);
         let half_quarter = Matrix4::rotation_z(std::f32::consts::PI / 4.0);
@@ -792,7 +792,7 @@ mod tests {
         assert_eq!(full_quarter * p, Tuple::point(-1.0, 0.0, 0.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_x_in_proportion_to_y() {
         let transform = Matrix4::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -800,7 +800,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(5.0, 3.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_x_in_proportion_to_z() {
         let transform = Matrix4::shearing(0.0, 1.0, 0.0, 0.0, 0.0, 0.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -808,7 +808,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(6.0, 3.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_y_in_proportion_to_x() {
         let transform = Matrix4::shearing(0.0, 0.0, 1.0, 0.0, 0.0, 0.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -816,7 +816,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(2.0, 5.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_y_in_proportion_to_z() {
         let transform = Matrix4::shearing(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -824,7 +824,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(2.0, 7.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_z_in_proportion_to_x() {
         let transform = Matrix4::shearing(0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -832,7 +832,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(2.0, 3.0, 6.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_shearing_transformation_moves_z_in_proportion_to_y() {
         let transform = Matrix4::shearing(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
         let p = Tuple::point(2.0, 3.0, 4.0);
@@ -840,7 +840,7 @@ mod tests {
         assert_eq!(transform * p, Tuple::point(2.0, 3.0, 7.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn individual_transformations_are_applied_in_sequence() {
         let p = Tuple::point(1.0, 0.0, 1.0);
         let a = Matrix4::rotation_x(std::f32::consts::PI / 2.0);
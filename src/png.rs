@@ -0,0 +1,221 @@
+//! Minimal PNG encoding.
+//!
+//! Images are written as 8-bit RGB with no scanline filtering, and the zlib stream uses
+//! stored (uncompressed) deflate blocks. Files are larger than a real compressor would
+//! produce, but every PNG reader accepts them and the encoder needs no dependencies.
+
+use crate::canvas::Canvas;
+use crate::tonemap::ToneMapping;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Largest payload of a single stored deflate block.
+const MAX_STORED_BLOCK: usize = 65_535;
+
+const COLOR_TYPE_RGB: u8 = 2;
+
+impl Canvas {
+    /// Encodes the canvas as an 8-bit RGB PNG after applying `tone_mapping`.
+    pub fn to_png(&self, tone_mapping: ToneMapping) -> Vec<u8> {
+        let mut scanlines = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for y in 0..self.height {
+            scanlines.push(0); // filter type: none
+            for x in 0..self.width {
+                scanlines.extend_from_slice(&tone_mapping.to_rgb8(self.pixel_at(x, y)));
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth, color type, compression, filter and interlace methods
+        header.extend_from_slice(&[8, COLOR_TYPE_RGB, 0, 0, 0]);
+
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len().div_ceil(MAX_STORED_BLOCK).max(1);
+    let mut stream = Vec::with_capacity(2 + data.len() + blocks * 5 + 4);
+    // Deflate with a 32K window and no preset dictionary; 0x7801 is divisible by 31
+    stream.extend_from_slice(&[0x78, 0x01]);
+
+    let mut chunks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if chunks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let is_final = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        stream.push(is_final as u8); // BFINAL, with BTYPE 00 (stored)
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(chunk);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by PNG chunks (and zip and gzip).
+fn crc32(data: &[u8]) -> u32 {
+    let crc = data.iter().fold(0xffff_ffff_u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    });
+    crc ^ 0xffff_ffff
+}
+
+/// Adler-32 checksum that ends a zlib stream.
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    // 5552 is the most bytes that can be summed before `b` might overflow a u32
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuple::Tuple;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn crc32_matches_the_standard_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn adler32_matches_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // Long enough to need the periodic modulo
+        let data = vec![0xffu8; 100_000];
+        let (a, b) = data.iter().fold((1u64, 0u64), |(a, b), &x| {
+            let a = (a + x as u64) % 65_521;
+            (a, (b + a) % 65_521)
+        });
+        assert_eq!(adler32(&data), ((b << 16) | a) as u32);
+    }
+
+    /// Splits a PNG into its chunks, checking each CRC.
+    fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        assert_eq!(png[..8], SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut pos = 8;
+        while pos < png.len() {
+            let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+            let body = &png[pos + 4..pos + 8 + len];
+            let crc =
+                u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+            assert_eq!(crc, crc32(body));
+            let kind = String::from_utf8(body[..4].to_vec()).unwrap();
+            chunks.push((kind, body[4..].to_vec()));
+            pos += 12 + len;
+        }
+        chunks
+    }
+
+    /// Inflates a zlib stream made only of stored blocks, checking the Adler-32.
+    fn inflate_stored(stream: &[u8]) -> Vec<u8> {
+        assert_eq!(((stream[0] as u16) << 8 | stream[1] as u16) % 31, 0);
+        let mut data = Vec::new();
+        let mut pos = 2;
+        loop {
+            let header = stream[pos];
+            assert_eq!(header & 0b110, 0, "only stored blocks are expected");
+            let len = u16::from_le_bytes([stream[pos + 1], stream[pos + 2]]);
+            let nlen = u16::from_le_bytes([stream[pos + 3], stream[pos + 4]]);
+            assert_eq!(len, !nlen);
+            data.extend_from_slice(&stream[pos + 5..pos + 5 + len as usize]);
+            pos += 5 + len as usize;
+            if header & 1 == 1 {
+                break;
+            }
+        }
+        let adler = u32::from_be_bytes(stream[pos..pos + 4].try_into().unwrap());
+        assert_eq!(adler, adler32(&data));
+        assert_eq!(pos + 4, stream.len());
+        data
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn png_header_describes_an_rgb_image() {
+        let png = Canvas::new(7, 3).to_png(ToneMapping::default());
+        let chunks = chunks(&png);
+        let kinds: Vec<&str> = chunks.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 7, 0, 0, 0, 3, 8, 2, 0, 0, 0]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn png_image_data_holds_unfiltered_scanlines() {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(1, 0, Tuple::color(1.0, 0.5, 0.0));
+        c.write_pixel(0, 1, Tuple::color(2.0, 0.2, 1.0));
+        let png = c.to_png(ToneMapping::default());
+        let scanlines = inflate_stored(&chunks(&png)[1].1);
+        assert_eq!(
+            scanlines,
+            [0, 0, 0, 0, 255, 128, 0, 0, 255, 51, 255, 0, 0, 0]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn large_images_span_several_deflate_blocks() {
+        let c = Canvas::new(200, 200);
+        let png = c.to_png(ToneMapping::default());
+        let scanlines = inflate_stored(&chunks(&png)[1].1);
+        assert_eq!(scanlines.len(), 200 * (1 + 200 * 3));
+        assert!(scanlines.len() > MAX_STORED_BLOCK);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn an_empty_image_still_has_a_valid_zlib_stream() {
+        let png = Canvas::new(0, 0).to_png(ToneMapping::default());
+        assert!(inflate_stored(&chunks(&png)[1].1).is_empty());
+    }
+}
@@ -0,0 +1,399 @@
+//! Reading and writing Netpbm pixmaps.
+//!
+//! PPM is the book’s output format: trivial to write, readable by most image tools, and
+//! easy to diff in tests. Plain `P3` files list every channel as decimal text; binary
+//! `P6` files store the same header followed by raw bytes.
+
+use std::fmt;
+
+use crate::canvas::Canvas;
+use crate::tonemap::ToneMapping;
+use crate::tuple::Tuple;
+
+/// The longest line `to_ppm` writes; some PPM readers reject longer ones.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Why a PPM file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PpmError {
+    /// The file does not start with `P3` or `P6`.
+    UnsupportedFormat(String),
+    /// The data ended before the named header field.
+    MissingField(&'static str),
+    /// A header field or plain pixel value is not a non-negative integer.
+    InvalidNumber { field: &'static str, value: String },
+    /// The maximum channel value is outside 1–65535.
+    InvalidMaxValue(u32),
+    /// A pixel value exceeds the maximum declared in the header.
+    ValueOutOfRange { value: u32, max: u32 },
+    /// The data holds fewer channel values than the header’s dimensions require.
+    Truncated { expected: usize, found: usize },
+}
+
+impl fmt::Display for PpmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PpmError::UnsupportedFormat(magic) => {
+                write!(f, "unsupported PPM format {magic:?}, expected P3 or P6")
+            }
+            PpmError::MissingField(field) => write!(f, "PPM header is missing the {field}"),
+            PpmError::InvalidNumber { field, value } => {
+                write!(f, "invalid PPM {field} {value:?}")
+            }
+            PpmError::InvalidMaxValue(max) => {
+                write!(f, "PPM maximum value {max} is outside 1–65535")
+            }
+            PpmError::ValueOutOfRange { value, max } => {
+                write!(f, "PPM value {value} exceeds the maximum of {max}")
+            }
+            PpmError::Truncated { expected, found } => write!(
+                f,
+                "PPM data ends after {found} of {expected} channel values"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PpmError {}
+
+impl Canvas {
+    /// Encodes the canvas as a plain-text `P3` PPM, clamping colors to [0, 1].
+    pub fn to_ppm(&self) -> String {
+        self.to_ppm_with(ToneMapping::default())
+    }
+
+    /// Encodes the canvas as a plain-text `P3` PPM after applying `tone_mapping`.
+    ///
+    /// Each row of pixels starts on a new line, and lines are wrapped to at most 70
+    /// characters.
+    pub fn to_ppm_with(&self, tone_mapping: ToneMapping) -> String {
+        let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
+        for y in 0..self.height {
+            let mut line = String::new();
+            for x in 0..self.width {
+                for value in tone_mapping.to_rgb8(self.pixel_at(x, y)) {
+                    let value = value.to_string();
+                    if !line.is_empty() && line.len() + 1 + value.len() > MAX_LINE_LENGTH {
+                        ppm.push_str(&line);
+                        ppm.push('\n');
+                        line.clear();
+                    }
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&value);
+                }
+            }
+            ppm.push_str(&line);
+            ppm.push('\n');
+        }
+        ppm
+    }
+
+    /// Encodes the canvas as a binary `P6` PPM after applying `tone_mapping`.
+    pub fn to_ppm_binary(&self, tone_mapping: ToneMapping) -> Vec<u8> {
+        let header = format!("P6\n{} {}\n255\n", self.width, self.height);
+        let mut data = Vec::with_capacity(header.len() + self.width * self.height * 3);
+        data.extend_from_slice(header.as_bytes());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                data.extend_from_slice(&tone_mapping.to_rgb8(self.pixel_at(x, y)));
+            }
+        }
+        data
+    }
+
+    /// Reads a `P3` or `P6` PPM, scaling channels so the maximum value becomes 1.0.
+    pub fn from_ppm(data: &[u8]) -> Result<Canvas, PpmError> {
+        let mut reader = Reader { data, pos: 0 };
+
+        let binary = match reader.token() {
+            Some(b"P3") => false,
+            Some(b"P6") => true,
+            Some(magic) => {
+                let magic = String::from_utf8_lossy(magic).into_owned();
+                return Err(PpmError::UnsupportedFormat(magic));
+            }
+            None => return Err(PpmError::MissingField("format")),
+        };
+        let width = reader.number("width")? as usize;
+        let height = reader.number("height")? as usize;
+        let max = reader.number("maximum value")?;
+        if !(1..=65535).contains(&max) {
+            return Err(PpmError::InvalidMaxValue(max));
+        }
+
+        let expected = width.saturating_mul(height).saturating_mul(3);
+        let values = if binary {
+            // Exactly one whitespace byte separates the header from the raster
+            reader.pos += 1;
+            reader.raster(expected, max)?
+        } else {
+            reader.plain_values(expected, max)?
+        };
+
+        let scale = 1.0 / max as f32;
+        let mut canvas = Canvas::new(width, height);
+        for (i, rgb) in values.chunks(3).enumerate() {
+            let color = Tuple::color(
+                rgb[0] as f32 * scale,
+                rgb[1] as f32 * scale,
+                rgb[2] as f32 * scale,
+            );
+            canvas.write_pixel(i % width, i / width, color);
+        }
+        Ok(canvas)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Returns the next whitespace-separated token, skipping `#` comments.
+    fn token(&mut self) -> Option<&'a [u8]> {
+        loop {
+            match self.data.get(self.pos)? {
+                b'#' => {
+                    while self.data.get(self.pos).is_some_and(|&b| b != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                b if b.is_ascii_whitespace() => self.pos += 1,
+                _ => break,
+            }
+        }
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+        {
+            self.pos += 1;
+        }
+        Some(&self.data[start..self.pos])
+    }
+
+    fn number(&mut self, field: &'static str) -> Result<u32, PpmError> {
+        let token = self.token().ok_or(PpmError::MissingField(field))?;
+        parse_number(token, field)
+    }
+
+    fn plain_values(&mut self, expected: usize, max: u32) -> Result<Vec<u32>, PpmError> {
+        let mut values = Vec::with_capacity(expected.min(self.data.len()));
+        while values.len() < expected {
+            let Some(token) = self.token() else {
+                return Err(PpmError::Truncated {
+                    expected,
+                    found: values.len(),
+                });
+            };
+            values.push(checked(parse_number(token, "pixel value")?, max)?);
+        }
+        Ok(values)
+    }
+
+    fn raster(&mut self, expected: usize, max: u32) -> Result<Vec<u32>, PpmError> {
+        let width = if max < 256 { 1 } else { 2 };
+        let raster = self.data.get(self.pos..).unwrap_or_default();
+        let found = raster.len() / width;
+        if found < expected {
+            return Err(PpmError::Truncated { expected, found });
+        }
+        raster
+            .chunks_exact(width)
+            .take(expected)
+            .map(|bytes| {
+                let value = bytes.iter().fold(0, |v, &b| (v << 8) | u32::from(b));
+                checked(value, max)
+            })
+            .collect()
+    }
+}
+
+fn parse_number(token: &[u8], field: &'static str) -> Result<u32, PpmError> {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| PpmError::InvalidNumber {
+            field,
+            value: String::from_utf8_lossy(token).into_owned(),
+        })
+}
+
+fn checked(value: u32, max: u32) -> Result<u32, PpmError> {
+    if value > max {
+        Err(PpmError::ValueOutOfRange { value, max })
+    } else {
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn constructing_the_ppm_header() {
+        let c = Canvas::new(5, 3);
+        let ppm = c.to_ppm();
+        let lines: Vec<&str> = ppm.lines().take(3).collect();
+        assert_eq!(lines, ["P3", "5 3", "255"]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn constructing_the_ppm_pixel_data() {
+        let mut c = Canvas::new(5, 3);
+        c.write_pixel(0, 0, Tuple::color(1.5, 0.0, 0.0));
+        c.write_pixel(2, 1, Tuple::color(0.0, 0.5, 0.0));
+        c.write_pixel(4, 2, Tuple::color(-0.5, 0.0, 1.0));
+        let ppm = c.to_ppm();
+        let lines: Vec<&str> = ppm.lines().skip(3).collect();
+        assert_eq!(
+            lines,
+            [
+                "255 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+                "0 0 0 0 0 0 0 128 0 0 0 0 0 0 0",
+                "0 0 0 0 0 0 0 0 0 0 0 0 0 0 255",
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn splitting_long_lines_in_ppm_files() {
+        let mut c = Canvas::new(10, 2);
+        for y in 0..2 {
+            for x in 0..10 {
+                c.write_pixel(x, y, Tuple::color(1.0, 0.8, 0.6));
+            }
+        }
+        let ppm = c.to_ppm();
+        let lines: Vec<&str> = ppm.lines().skip(3).collect();
+        assert_eq!(
+            lines,
+            [
+                "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                "153 255 204 153 255 204 153 255 204 153 255 204 153",
+                "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                "153 255 204 153 255 204 153 255 204 153 255 204 153",
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn ppm_files_are_terminated_by_a_newline() {
+        let c = Canvas::new(5, 3);
+        assert!(c.to_ppm().ends_with('\n'));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn binary_ppm_stores_raw_bytes_after_the_header() {
+        let mut c = Canvas::new(2, 1);
+        c.write_pixel(1, 0, Tuple::color(1.0, 0.5, 2.0));
+        let data = c.to_ppm_binary(ToneMapping::default());
+        assert_eq!(data, b"P6\n2 1\n255\n\0\0\0\xff\x80\xff");
+    }
+
+    fn sample_canvas() -> Canvas {
+        let mut c = Canvas::new(3, 2);
+        c.write_pixel(0, 0, Tuple::color(1.0, 0.0, 0.0));
+        c.write_pixel(2, 1, Tuple::color(0.2, 0.4, 0.6));
+        c
+    }
+
+    fn assert_close(a: &Canvas, b: &Canvas) {
+        assert_eq!((a.width, a.height), (b.width, b.height));
+        for y in 0..a.height {
+            for x in 0..a.width {
+                let (p, q) = (a.pixel_at(x, y), b.pixel_at(x, y));
+                for (u, v) in [(p.x(), q.x()), (p.y(), q.y()), (p.z(), q.z())] {
+                    assert!((u - v).abs() <= 0.5 / 255.0, "({x}, {y}): {p:?} vs {q:?}");
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn plain_and_binary_ppm_read_back_into_a_canvas() {
+        let c = sample_canvas();
+        assert_close(&Canvas::from_ppm(c.to_ppm().as_bytes()).unwrap(), &c);
+        let binary = c.to_ppm_binary(ToneMapping::default());
+        assert_close(&Canvas::from_ppm(&binary).unwrap(), &c);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn reading_ppm_skips_comments_and_scales_by_the_maximum() {
+        let data = b"P3\n# made by hand\n1 1 # one pixel\n10\n10 5 0\n";
+        let c = Canvas::from_ppm(data).unwrap();
+        assert!(c.pixel_at(0, 0).rgb_eq(Tuple::color(1.0, 0.5, 0.0)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn reading_sixteen_bit_binary_ppm() {
+        let data = b"P6 1 1 65535\n\xff\xff\x80\x00\x00\x00";
+        let c = Canvas::from_ppm(data).unwrap();
+        assert!(c
+            .pixel_at(0, 0)
+            .rgb_eq(Tuple::color(1.0, 32768.0 / 65535.0, 0.0)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn malformed_ppm_headers_are_reported() {
+        assert_eq!(
+            Canvas::from_ppm(b"P5\n1 1\n255\n").err(),
+            Some(PpmError::UnsupportedFormat("P5".to_string()))
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"").err(),
+            Some(PpmError::MissingField("format"))
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n4").err(),
+            Some(PpmError::MissingField("height"))
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P3\nfour 4\n255\n").err(),
+            Some(PpmError::InvalidNumber {
+                field: "width",
+                value: "four".to_string()
+            })
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n1 1\n0\n0 0 0").err(),
+            Some(PpmError::InvalidMaxValue(0))
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn malformed_ppm_pixel_data_is_reported() {
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n1 1\n255\n0 256 0").err(),
+            Some(PpmError::ValueOutOfRange {
+                value: 256,
+                max: 255
+            })
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P3\n2 1\n255\n0 0 0 0").err(),
+            Some(PpmError::Truncated {
+                expected: 6,
+                found: 4
+            })
+        );
+        assert_eq!(
+            Canvas::from_ppm(b"P6\n2 1\n255\n\0\0\0").err(),
+            Some(PpmError::Truncated {
+                expected: 6,
+                found: 3
+            })
+        );
+        let error = Canvas::from_ppm(b"P3\n2 1\n255\n0").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "PPM data ends after 1 of 6 channel values"
+        );
+    }
+}
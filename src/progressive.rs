@@ -0,0 +1,822 @@
+//! Progressive rendering for interactive previews.
+//!
+//! Two strategies trade an immediate rough image for later refinement:
+//!
+//! * `Accumulator` adds one jittered sample per pixel to a running sum, so the image
+//!   starts out aliased along edges and converges toward an antialiased result the
+//!   longer the view stays still.
+//! * `Interleaver` renders a sparse subset of pixels first and fills the gaps from the
+//!   nearest rendered pixel, then fills in the rest over subsequent passes.
+
+#[cfg(feature = "web")]
+use js_sys::Uint8ClampedArray;
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "web")]
+use web_sys::ImageData;
+
+use crate::camera::Camera;
+use crate::canvas::Canvas;
+use crate::integrator::Integrator;
+use crate::rng::Rng;
+#[cfg(feature = "web")]
+use crate::rng::DEFAULT_SEED;
+#[cfg(feature = "web")]
+use crate::scene::{
+    check_pixels, check_scene_buffer, parse_camera, parse_tone_mapping, SceneBufferError,
+    SceneStamp, WorldCache,
+};
+use crate::tuple::Tuple;
+use crate::world::World;
+
+/// Running per-pixel sum of samples for one camera and world.
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    sums: Vec<f32>,
+    samples: u32,
+    rng: Rng,
+    integrator: Integrator,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize, seed: u64) -> Accumulator {
+        Accumulator {
+            width,
+            height,
+            sums: vec![0.0; width * height * 3],
+            samples: 0,
+            rng: Rng::new(seed),
+            integrator: Integrator::default(),
+        }
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    /// Switches how samples are traced, discarding samples taken with the old one.
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        if integrator != self.integrator {
+            self.integrator = integrator;
+            self.reset();
+        }
+    }
+
+    /// Number of samples accumulated per pixel so far.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Discards all accumulated samples.
+    pub fn reset(&mut self) {
+        self.sums.fill(0.0);
+        self.samples = 0;
+    }
+
+    /// Traces one jittered sample through every pixel and adds it to the sums.
+    ///
+    /// # Panics
+    /// Panics if the camera size differs from the accumulator’s.
+    pub fn add_sample(&mut self, camera: &Camera, world: &World) {
+        assert_eq!(
+            camera.size(),
+            (self.width, self.height),
+            "camera size must match the accumulator"
+        );
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let dx = self.rng.next_f32();
+                let dy = self.rng.next_f32();
+                let ray = camera.ray_for_sample(x, y, dx, dy);
+                let color = self.integrator.color_at(world, ray, &mut self.rng);
+
+                let i = (y * self.width + x) * 3;
+                self.sums[i] += color.x();
+                self.sums[i + 1] += color.y();
+                self.sums[i + 2] += color.z();
+            }
+        }
+        self.samples += 1;
+    }
+
+    /// Returns the mean of all accumulated samples, or black before the first one.
+    pub fn average(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width, self.height);
+        if self.samples == 0 {
+            return canvas;
+        }
+
+        let scale = 1.0 / self.samples as f32;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = (y * self.width + x) * 3;
+                let color = Tuple::color(
+                    self.sums[i] * scale,
+                    self.sums[i + 1] * scale,
+                    self.sums[i + 2] * scale,
+                );
+                canvas.write_pixel(x, y, color);
+            }
+        }
+        canvas
+    }
+}
+
+/// Splits a frame into a coarse first pass and progressively finer refinements.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PreviewMode {
+    /// Half the pixels in a checkerboard pattern, then the other half.
+    Checkerboard = 0,
+    /// Every second pixel in each direction (¼ resolution), then the rest.
+    Quarter = 1,
+    /// Every fourth pixel in each direction (1/16 resolution), then ¼, then the rest.
+    #[default]
+    Sixteenth = 2,
+}
+
+impl PreviewMode {
+    fn pass_count(self) -> usize {
+        match self {
+            PreviewMode::Checkerboard | PreviewMode::Quarter => 2,
+            PreviewMode::Sixteenth => 3,
+        }
+    }
+
+    /// The pixel whose rendered color stands in for (`x`, `y`) once `pass` is done.
+    ///
+    /// A pixel that is its own source has been rendered by the end of that pass.
+    fn source(
+        self,
+        pass: usize,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> (usize, usize) {
+        if pass + 1 >= self.pass_count() {
+            return (x, y);
+        }
+
+        match self {
+            PreviewMode::Checkerboard => {
+                if (x + y).is_multiple_of(2) {
+                    (x, y)
+                } else if x > 0 {
+                    (x - 1, y)
+                } else if x + 1 < width {
+                    (x + 1, y)
+                } else if y > 0 {
+                    (x, y - 1)
+                } else {
+                    (x, (y + 1).min(height - 1))
+                }
+            }
+            PreviewMode::Quarter | PreviewMode::Sixteenth => {
+                let stride = 1 << (self.pass_count() - 1 - pass);
+                (x - x % stride, y - y % stride)
+            }
+        }
+    }
+}
+
+/// Renders a frame in interleaved passes, filling gaps by nearest neighbor.
+///
+/// Across all passes every pixel is traced exactly once, so a complete interleaved
+/// render costs the same as a regular one.
+pub struct Interleaver {
+    mode: PreviewMode,
+    canvas: Canvas,
+    pass: usize,
+}
+
+impl Interleaver {
+    pub fn new(width: usize, height: usize, mode: PreviewMode) -> Interleaver {
+        Interleaver {
+            mode,
+            canvas: Canvas::new(width, height),
+            pass: 0,
+        }
+    }
+
+    /// Number of passes rendered so far.
+    pub fn passes(&self) -> usize {
+        self.pass
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pass >= self.mode.pass_count()
+    }
+
+    /// The current image, with unrendered pixels filled from their nearest neighbor.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Traces the pixels belonging to the next pass. Does nothing once complete.
+    ///
+    /// # Panics
+    /// Panics if the camera size differs from the interleaver’s.
+    pub fn render_pass(&mut self, camera: &Camera, world: &World) {
+        let (width, height) = (self.canvas.width, self.canvas.height);
+        assert_eq!(
+            camera.size(),
+            (width, height),
+            "camera size must match the interleaver"
+        );
+        if self.is_complete() {
+            return;
+        }
+
+        let pass = self.pass;
+        let mode = self.mode;
+        let rendered_before =
+            |x, y| pass > 0 && mode.source(pass - 1, x, y, width, height) == (x, y);
+
+        for y in 0..height {
+            for x in 0..width {
+                if mode.source(pass, x, y, width, height) == (x, y)
+                    && !rendered_before(x, y)
+                {
+                    let color = world.color_at(camera.ray_for_pixel(x, y));
+                    self.canvas.write_pixel(x, y, color);
+                }
+            }
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = mode.source(pass, x, y, width, height);
+                if (sx, sy) != (x, y) {
+                    let color = self.canvas.pixel_at(sx, sy);
+                    self.canvas.write_pixel(x, y, color);
+                }
+            }
+        }
+
+        self.pass += 1;
+    }
+}
+
+/// The scene buffer's stamp and render settings from a previous frame, used to detect
+/// when accumulated progress no longer applies.
+#[cfg(feature = "web")]
+#[derive(Default)]
+struct SceneSnapshot {
+    stamp: Option<SceneStamp>,
+    width: usize,
+    height: usize,
+    fov: f32,
+}
+
+#[cfg(feature = "web")]
+impl SceneSnapshot {
+    /// Records the new settings, returning true if they differ from the last ones.
+    fn update(
+        &mut self,
+        buffer: &[u8],
+        width: usize,
+        height: usize,
+        fov: f32,
+    ) -> Result<bool, SceneBufferError> {
+        let stamp = Some(SceneStamp::read(buffer)?);
+        let changed = self.stamp != stamp
+            || self.width != width
+            || self.height != height
+            || self.fov != fov;
+        if changed {
+            *self = SceneSnapshot {
+                stamp,
+                width,
+                height,
+                fov,
+            };
+        }
+        Ok(changed)
+    }
+}
+
+/// Renders a scene buffer progressively across successive calls.
+///
+/// Every `render` call adds one sample per pixel and returns the current average. The
+/// accumulated samples are discarded automatically whenever the scene, dimensions or
+/// field of view differ from the previous call. Changing only the tone mapping keeps
+/// them, since it is applied to the average each time it is displayed.
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+pub struct ProgressiveRenderer {
+    seed: u64,
+    integrator: Integrator,
+    accumulator: Option<Accumulator>,
+    snapshot: SceneSnapshot,
+    world: WorldCache,
+    /// RGBA bytes of the last image returned by `renderInto`
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+impl ProgressiveRenderer {
+    /// Creates a renderer whose sample jitter is derived from `seed`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: Option<u32>) -> ProgressiveRenderer {
+        ProgressiveRenderer {
+            seed: seed.map_or(DEFAULT_SEED, u64::from),
+            integrator: Integrator::default(),
+            accumulator: None,
+            snapshot: SceneSnapshot::default(),
+            world: WorldCache::new(),
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Adds one sample per pixel and returns the averaged image.
+    pub fn render(
+        &mut self,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        fov: f32,
+    ) -> Result<ImageData, JsValue> {
+        let accumulator = self.accumulate(buffer, width as usize, height as usize, fov)?;
+        Ok(accumulator
+            .average()
+            .to_image_data_with(parse_tone_mapping(buffer)?))
+    }
+
+    /// Adds one sample per pixel and copies the averaged image into `pixels` as RGBA
+    /// bytes, like `renderFromBufferInto`.
+    #[wasm_bindgen(js_name = renderInto)]
+    pub fn render_into(
+        &mut self,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        fov: f32,
+        pixels: &Uint8ClampedArray,
+    ) -> Result<(), JsValue> {
+        check_pixels(pixels, width, height)?;
+        let accumulator = self.accumulate(buffer, width as usize, height as usize, fov)?;
+        let average = accumulator.average();
+        average
+            .copy_rgba8_into(parse_tone_mapping(buffer)?, &mut self.pixels, pixels)
+            .map_err(JsValue::from_str)
+    }
+
+    /// Number of samples per pixel in the current image.
+    #[wasm_bindgen(getter)]
+    pub fn samples(&self) -> u32 {
+        self.accumulator.as_ref().map_or(0, Accumulator::samples)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    /// Switches integrators. Samples taken with the previous one are discarded.
+    #[wasm_bindgen(setter)]
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        if integrator != self.integrator {
+            self.integrator = integrator;
+            self.reset();
+        }
+    }
+
+    /// Discards the accumulated samples so the next `render` starts over.
+    pub fn reset(&mut self) {
+        self.accumulator = None;
+    }
+}
+
+#[cfg(feature = "web")]
+impl ProgressiveRenderer {
+    fn accumulate(
+        &mut self,
+        buffer: &[u8],
+        width: usize,
+        height: usize,
+        fov: f32,
+    ) -> Result<&Accumulator, SceneBufferError> {
+        check_scene_buffer(buffer)?;
+        if self.snapshot.update(buffer, width, height, fov)? {
+            self.accumulator = None;
+        }
+
+        let camera = parse_camera(buffer, width, height, fov)?;
+        self.world.update(buffer)?;
+        let (seed, integrator) = (self.seed, self.integrator);
+        let accumulator = self.accumulator.get_or_insert_with(|| {
+            let mut accumulator = Accumulator::new(width, height, seed);
+            accumulator.set_integrator(integrator);
+            accumulator
+        });
+        accumulator.add_sample(&camera, self.world.world());
+        Ok(accumulator)
+    }
+}
+
+/// Renders a scene buffer in interleaved low-resolution passes across successive calls.
+///
+/// The first `render` call traces only a sparse subset of pixels and fills the gaps by
+/// nearest neighbor, so something is on screen quickly while the camera moves. Each
+/// further call refines the image until `isComplete`. Changing the scene, dimensions or
+/// field of view starts over from the coarsest pass; changing only the tone mapping
+/// does not.
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+pub struct PreviewRenderer {
+    mode: PreviewMode,
+    interleaver: Option<Interleaver>,
+    snapshot: SceneSnapshot,
+    world: WorldCache,
+    /// RGBA bytes of the last image returned by `renderInto`
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+impl PreviewRenderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(mode: Option<PreviewMode>) -> PreviewRenderer {
+        PreviewRenderer {
+            mode: mode.unwrap_or_default(),
+            interleaver: None,
+            snapshot: SceneSnapshot::default(),
+            world: WorldCache::new(),
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Renders the next pass and returns the current image.
+    ///
+    /// Once the image is complete, further calls return it without tracing any rays.
+    pub fn render(
+        &mut self,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        fov: f32,
+    ) -> Result<ImageData, JsValue> {
+        let interleaver = self.refine(buffer, width as usize, height as usize, fov)?;
+        Ok(interleaver
+            .canvas()
+            .to_image_data_with(parse_tone_mapping(buffer)?))
+    }
+
+    /// Renders the next pass and copies the current image into `pixels` as RGBA bytes,
+    /// like `renderFromBufferInto`.
+    #[wasm_bindgen(js_name = renderInto)]
+    pub fn render_into(
+        &mut self,
+        buffer: &[u8],
+        width: u32,
+        height: u32,
+        fov: f32,
+        pixels: &Uint8ClampedArray,
+    ) -> Result<(), JsValue> {
+        check_pixels(pixels, width, height)?;
+        // The scratch bytes are taken out while `refine` borrows the renderer
+        let mut scratch = std::mem::take(&mut self.pixels);
+        let interleaver = self.refine(buffer, width as usize, height as usize, fov)?;
+        let copied = interleaver.canvas().copy_rgba8_into(
+            parse_tone_mapping(buffer)?,
+            &mut scratch,
+            pixels,
+        );
+        self.pixels = scratch;
+        copied.map_err(JsValue::from_str)
+    }
+
+    /// Whether every pixel of the current image has been traced.
+    #[wasm_bindgen(getter, js_name = isComplete)]
+    pub fn is_complete(&self) -> bool {
+        self.interleaver
+            .as_ref()
+            .is_some_and(Interleaver::is_complete)
+    }
+
+    /// Discards the current image so the next `render` starts from the coarsest pass.
+    pub fn reset(&mut self) {
+        self.interleaver = None;
+    }
+}
+
+#[cfg(feature = "web")]
+impl PreviewRenderer {
+    fn refine(
+        &mut self,
+        buffer: &[u8],
+        width: usize,
+        height: usize,
+        fov: f32,
+    ) -> Result<&Interleaver, SceneBufferError> {
+        check_scene_buffer(buffer)?;
+        if self.snapshot.update(buffer, width, height, fov)? {
+            self.interleaver = None;
+        }
+
+        let mode = self.mode;
+        let interleaver = self
+            .interleaver
+            .get_or_insert_with(|| Interleaver::new(width, height, mode));
+        if !interleaver.is_complete() {
+            let camera = parse_camera(buffer, width, height, fov)?;
+            self.world.update(buffer)?;
+            interleaver.render_pass(&camera, self.world.world());
+        }
+        Ok(interleaver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::light::Light;
+    use crate::material::Material;
+    #[cfg(feature = "web")]
+    use crate::scene::{
+        add_sphere_to_buffer, get_scene_buffer_size, initialize_scene_buffer,
+        write_camera_to_buffer, write_light_to_buffer, write_sphere_color_to_buffer,
+        write_tone_mapping_to_buffer,
+    };
+    use crate::sphere::Sphere;
+    #[cfg(feature = "web")]
+    use crate::tonemap::ToneMapper;
+    use crate::transform::{view_transform, Transform};
+    use wasm_bindgen_test::*;
+
+    fn camera(width: usize, height: usize) -> Camera {
+        let mut c = Camera::new(width, height, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        c
+    }
+
+    /// A world whose every ray sees the same unlit color from inside a large sphere.
+    fn uniform_world(color: Tuple) -> World {
+        let mut sphere = Sphere::new();
+        sphere
+            .set_transform(Transform::new().scale(100.0, 100.0, 100.0))
+            .unwrap();
+        sphere.material = Material {
+            color,
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Default::default()
+        };
+        let mut w = World::new();
+        w.objects.push(sphere);
+        w.light = Some(Light::new(
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        w
+    }
+
+    #[cfg(feature = "web")]
+    fn scene_buffer() -> Vec<u8> {
+        let mut buffer = vec![0u8; get_scene_buffer_size(1) as usize];
+        initialize_scene_buffer(&mut buffer);
+        write_camera_to_buffer(&mut buffer, 0.0, 0.0, -5.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        buffer
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn an_empty_accumulator_averages_to_black() {
+        let a = Accumulator::new(3, 2, 1);
+        assert_eq!(a.samples(), 0);
+        assert_eq!(a.average().pixel_at(2, 1), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn accumulating_samples_of_a_uniform_world_averages_to_its_color() {
+        let color = Tuple::color(0.2, 0.4, 0.6);
+        let w = uniform_world(color);
+        let c = camera(4, 3);
+        let mut a = Accumulator::new(4, 3, 1);
+        for _ in 0..5 {
+            a.add_sample(&c, &w);
+        }
+        assert_eq!(a.samples(), 5);
+        let image = a.average();
+        for y in 0..3 {
+            for x in 0..4 {
+                assert!(image.pixel_at(x, y).rgb_eq(color));
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn accumulated_samples_converge_on_the_pixel_color() {
+        let w = World::default();
+        let c = camera(11, 11);
+        let mut a = Accumulator::new(11, 11, 1);
+        for _ in 0..16 {
+            a.add_sample(&c, &w);
+        }
+        let averaged = a.average();
+        let single = c.render(&w);
+
+        // Interior pixels converge on the same shade as the center-sampled render
+        assert!((averaged.pixel_at(5, 5).x() - single.pixel_at(5, 5).x()).abs() < 0.02);
+        // Background stays black
+        assert_eq!(averaged.pixel_at(0, 0), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn the_same_seed_accumulates_the_same_image() {
+        let w = World::default();
+        let c = camera(8, 8);
+        let mut a = Accumulator::new(8, 8, 99);
+        let mut b = Accumulator::new(8, 8, 99);
+        a.add_sample(&c, &w);
+        b.add_sample(&c, &w);
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(a.average().pixel_at(x, y), b.average().pixel_at(x, y));
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn switching_integrators_discards_samples() {
+        let w = World::default();
+        let c = camera(4, 4);
+        let mut a = Accumulator::new(4, 4, 1);
+        a.add_sample(&c, &w);
+        a.set_integrator(Integrator::Whitted);
+        assert_eq!(a.samples(), 1);
+        a.set_integrator(Integrator::PathTracing);
+        assert_eq!(a.integrator(), Integrator::PathTracing);
+        assert_eq!(a.samples(), 0);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn resetting_an_accumulator_discards_samples() {
+        let w = World::default();
+        let c = camera(4, 4);
+        let mut a = Accumulator::new(4, 4, 1);
+        a.add_sample(&c, &w);
+        a.reset();
+        assert_eq!(a.samples(), 0);
+        assert_eq!(a.average().pixel_at(2, 2), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[cfg(feature = "web")]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn progressive_renderer_accumulates_while_the_scene_is_unchanged() {
+        let buffer = scene_buffer();
+        let mut r = ProgressiveRenderer::new(None);
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+        assert_eq!(r.samples(), 2);
+    }
+
+    #[cfg(feature = "web")]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn progressive_renderer_resets_when_the_scene_changes() {
+        let mut buffer = scene_buffer();
+        let mut r = ProgressiveRenderer::new(None);
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+
+        write_camera_to_buffer(&mut buffer, 1.0, 0.0, -5.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+        assert_eq!(r.samples(), 1);
+
+        r.accumulate(&buffer, 4, 4, 0.5).unwrap();
+        assert_eq!(r.samples(), 1);
+
+        r.accumulate(&buffer, 8, 4, 0.5).unwrap();
+        assert_eq!(r.samples(), 1);
+
+        write_light_to_buffer(&mut buffer, -10.0, 10.0, -10.0, 1.0, 1.0, 1.0);
+        r.accumulate(&buffer, 8, 4, 0.5).unwrap();
+        assert_eq!(r.samples(), 1);
+
+        // Object writes advance the buffer's write counter
+        let id = add_sphere_to_buffer(&mut buffer).unwrap();
+        r.accumulate(&buffer, 8, 4, 0.5).unwrap();
+        r.accumulate(&buffer, 8, 4, 0.5).unwrap();
+        write_sphere_color_to_buffer(&mut buffer, id, 1.0, 0.0, 0.0).unwrap();
+        r.accumulate(&buffer, 8, 4, 0.5).unwrap();
+        assert_eq!(r.samples(), 1);
+    }
+
+    #[cfg(feature = "web")]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn progressive_renderer_keeps_samples_when_only_tone_mapping_changes() {
+        let mut buffer = scene_buffer();
+        let mut r = ProgressiveRenderer::new(None);
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+
+        write_tone_mapping_to_buffer(&mut buffer, ToneMapper::Aces, 1.0, true);
+        r.accumulate(&buffer, 4, 4, 1.0).unwrap();
+        assert_eq!(r.samples(), 2);
+    }
+
+    fn assert_canvases_equal(a: &Canvas, b: &Canvas) {
+        for y in 0..a.height {
+            for x in 0..a.width {
+                assert_eq!(a.pixel_at(x, y), b.pixel_at(x, y), "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn sixteenth_mode_fills_four_by_four_blocks_on_the_first_pass() {
+        let w = World::default();
+        let c = camera(11, 11);
+        let full = c.render(&w);
+        let mut i = Interleaver::new(11, 11, PreviewMode::Sixteenth);
+
+        i.render_pass(&c, &w);
+        assert_eq!(i.passes(), 1);
+        assert!(!i.is_complete());
+        assert_eq!(i.canvas().pixel_at(4, 4), full.pixel_at(4, 4));
+        assert_eq!(i.canvas().pixel_at(7, 5), full.pixel_at(4, 4));
+        assert_eq!(i.canvas().pixel_at(10, 10), full.pixel_at(8, 8));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn every_preview_mode_converges_on_the_full_render() {
+        let w = World::default();
+        let c = camera(11, 9);
+        let full = c.render(&w);
+
+        for mode in [
+            PreviewMode::Checkerboard,
+            PreviewMode::Quarter,
+            PreviewMode::Sixteenth,
+        ] {
+            let mut i = Interleaver::new(11, 9, mode);
+            while !i.is_complete() {
+                i.render_pass(&c, &w);
+            }
+            assert_eq!(i.passes(), mode.pass_count());
+            assert_canvases_equal(i.canvas(), &full);
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn checkerboard_mode_fills_from_the_left_neighbor() {
+        let w = World::default();
+        let c = camera(11, 11);
+        let full = c.render(&w);
+        let mut i = Interleaver::new(11, 11, PreviewMode::Checkerboard);
+
+        i.render_pass(&c, &w);
+        assert_eq!(i.canvas().pixel_at(5, 5), full.pixel_at(5, 5));
+        assert_eq!(i.canvas().pixel_at(6, 5), full.pixel_at(5, 5));
+        assert_eq!(i.canvas().pixel_at(0, 1), full.pixel_at(1, 1));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn each_pixel_is_traced_exactly_once_across_passes() {
+        for mode in [
+            PreviewMode::Checkerboard,
+            PreviewMode::Quarter,
+            PreviewMode::Sixteenth,
+        ] {
+            let (width, height) = (7, 6);
+            let mut traced = vec![0; width * height];
+            for pass in 0..mode.pass_count() {
+                for y in 0..height {
+                    for x in 0..width {
+                        let own = mode.source(pass, x, y, width, height) == (x, y);
+                        let before = pass > 0
+                            && mode.source(pass - 1, x, y, width, height) == (x, y);
+                        if own && !before {
+                            traced[y * width + x] += 1;
+                        }
+                    }
+                }
+            }
+            assert!(traced.iter().all(|&n| n == 1), "{mode:?}");
+        }
+    }
+
+    #[cfg(feature = "web")]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn preview_renderer_refines_until_complete_and_restarts_on_change() {
+        let mut buffer = scene_buffer();
+        let mut r = PreviewRenderer::new(Some(PreviewMode::Quarter));
+        r.refine(&buffer, 4, 4, 1.0).unwrap();
+        assert!(!r.is_complete());
+        r.refine(&buffer, 4, 4, 1.0).unwrap();
+        assert!(r.is_complete());
+        assert_eq!(r.refine(&buffer, 4, 4, 1.0).unwrap().passes(), 2);
+
+        write_camera_to_buffer(&mut buffer, 1.0, 0.0, -5.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        assert_eq!(r.refine(&buffer, 4, 4, 1.0).unwrap().passes(), 1);
+        assert!(!r.is_complete());
+    }
+}
@@ -26,7 +26,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn creating_and_querying_a_ray() {
         let origin = Tuple::point(1.0, 2.0, 3.0);
         let direction = Tuple::vector(4.0, 5.0, 6.0);
@@ -36,7 +36,7 @@ mod tests {
         assert_eq!(r.direction, direction);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn computing_a_point_from_a_distance() {
         let r = Ray::new(Tuple::point(2.0, 3.0, 4.0), Tuple::vector(1.0, 0.0, 0.0));
 
@@ -46,7 +46,7 @@ mod tests {
         assert_eq!(r.position(2.5), Tuple::point(4.5, 3.0, 4.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn translating_a_ray() {
         let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
         let m = Matrix4::translation(3.0, 4.0, 5.0);
@@ -57,7 +57,7 @@ mod tests {
         assert_eq!(r2.direction, Tuple::vector(0.0, 1.0, 0.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn scaling_a_ray() {
         let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
         let m = Matrix4::scaling(2.0, 3.0, 4.0);
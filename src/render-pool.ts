@@ -0,0 +1,73 @@
+import init, { SharedRender } from '../dist/wasm/penumbra-threads.js'
+
+/**
+ * A pool of workers that render each frame together over shared wasm memory. Workers
+ * claim rows from a shared counter until none are left, so the load balances itself.
+ */
+export class RenderPool {
+  private constructor(private workers: Worker[]) {}
+
+  static async create(size = navigator.hardwareConcurrency): Promise<RenderPool> {
+    const module = await WebAssembly.compileStreaming(
+      fetch(new URL('../dist/wasm/penumbra-threads_bg.wasm', import.meta.url))
+    )
+    const { memory } = await init({ module_or_path: module })
+
+    const workers = Array.from(
+      { length: Math.max(1, size) },
+      () => new Worker(new URL('./render-thread.js', import.meta.url), { type: 'module' })
+    )
+    await Promise.all(
+      workers.map((worker) => {
+        const ready = reply(worker, 'ready')
+        worker.postMessage({ type: 'init', module, memory })
+        return ready
+      })
+    )
+    return new RenderPool(workers)
+  }
+
+  get size(): number {
+    return this.workers.length
+  }
+
+  async render(buffer: Uint8Array, width: number, height: number, fov: number) {
+    const frame = new SharedRender(buffer, width, height, fov)
+    try {
+      await Promise.all(
+        this.workers.map((worker) => {
+          const done = reply(worker, 'done')
+          worker.postMessage({ type: 'render', handle: frame.handle })
+          return done
+        })
+      )
+      return frame.toImageData()
+    } finally {
+      frame.free()
+    }
+  }
+
+  terminate() {
+    for (const worker of this.workers) worker.terminate()
+    this.workers = []
+  }
+}
+
+function reply(worker: Worker, type: string): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const onMessage = (e: MessageEvent) => {
+      if (e.data?.type !== type && e.data?.type !== 'error') return
+      worker.removeEventListener('message', onMessage)
+      worker.removeEventListener('error', onError)
+      if (e.data.type === 'error') reject(new Error(e.data.message))
+      else resolve()
+    }
+    const onError = (e: ErrorEvent) => {
+      worker.removeEventListener('message', onMessage)
+      worker.removeEventListener('error', onError)
+      reject(e.error ?? new Error(e.message))
+    }
+    worker.addEventListener('message', onMessage)
+    worker.addEventListener('error', onError)
+  })
+}
@@ -0,0 +1,23 @@
+import init, { renderSharedRows } from '../dist/wasm/penumbra-threads.js'
+
+type Message =
+  | { type: 'init'; module: WebAssembly.Module; memory: WebAssembly.Memory }
+  | { type: 'render'; handle: number }
+
+self.onmessage = async (e: MessageEvent<Message>) => {
+  const message = e.data
+  try {
+    switch (message.type) {
+      case 'init':
+        await init({ module_or_path: message.module, memory: message.memory })
+        self.postMessage({ type: 'ready' })
+        break
+      case 'render':
+        self.postMessage({ type: 'done', rows: renderSharedRows(message.handle) })
+        break
+    }
+  } catch (error) {
+    // Report the failure so the pool's pending request rejects instead of hanging
+    self.postMessage({ type: 'error', message: String(error) })
+  }
+}
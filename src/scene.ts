@@ -1,31 +1,57 @@
 import {
   getSceneBufferSize,
+  initializeSceneBuffer,
   writeCameraToBuffer,
+  writeProjectionToBuffer,
+  writeToneMappingToBuffer,
   writeLightToBuffer,
-  initializeSphereInBuffer,
+  addSphereToBuffer,
   writeSphereColorToBuffer,
   writeSphereMaterialToBuffer,
+  writeSphereEmissionToBuffer,
+  writeSphereShadingToBuffer,
   writeSphereTransformToBuffer,
+  sceneBufferToJSON,
+  sceneBufferFromJSON,
+  pickFromBuffer,
   Transform,
+  Projection,
+  ShadingModel,
+  ToneMapper,
 } from '../dist/wasm/penumbra-simd.js'
 
 const MAX_SPHERES = 256
 
+interface MaterialConfig {
+  ambient: number
+  diffuse: number
+  specular: number
+  shininess: number
+  /** Light given off by the surface itself. Defaults to black. */
+  emission?: [number, number, number]
+  /** Defaults to `ShadingModel.Phong`. */
+  shadingModel?: ShadingModel
+  /** Microfacet roughness for Cook-Torrance, 0 to 1. Defaults to 0.5. */
+  roughness?: number
+  /** 0 for dielectrics, 1 for metals, used by Cook-Torrance. Defaults to 0. */
+  metalness?: number
+}
+
 interface SphereConfig {
   transform?: Transform
   color?: [number, number, number]
-  material?: { ambient: number; diffuse: number; specular: number; shininess: number }
+  material?: MaterialConfig
 }
 
 export class Scene {
   private buffer: SharedArrayBuffer
   private view: Uint8Array
-  private sphereCount = 0
 
   constructor() {
     const totalSize = getSceneBufferSize(MAX_SPHERES)
     this.buffer = new SharedArrayBuffer(totalSize)
     this.view = new Uint8Array(this.buffer)
+    initializeSceneBuffer(this.view)
   }
 
   lookAt(params: {
@@ -39,6 +65,23 @@ export class Scene {
     writeCameraToBuffer(this.view, fromX, fromY, fromZ, toX, toY, toZ, upX, upY, upZ)
   }
 
+  setProjection(projection: Projection) {
+    writeProjectionToBuffer(this.view, projection)
+  }
+
+  /**
+   * Sets how rendered colors are converted for display: `exposure` is in stops, and
+   * `srgb` applies gamma encoding. Defaults to clamping linear values.
+   */
+  setToneMapping(params: { mapper?: ToneMapper; exposure?: number; srgb?: boolean }) {
+    writeToneMappingToBuffer(
+      this.view,
+      params.mapper ?? ToneMapper.Clamp,
+      params.exposure ?? 0,
+      params.srgb ?? false
+    )
+  }
+
   setLight(params: { position: [number, number, number]; intensity: [number, number, number] }) {
     const [posX, posY, posZ] = params.position
     const [intR, intG, intB] = params.intensity
@@ -46,8 +89,7 @@ export class Scene {
   }
 
   addSphere(config?: SphereConfig): number {
-    const id = this.sphereCount++
-    initializeSphereInBuffer(this.view, id)
+    const id = addSphereToBuffer(this.view)
 
     if (config?.transform) {
       writeSphereTransformToBuffer(config.transform, this.view, id)
@@ -57,8 +99,7 @@ export class Scene {
       writeSphereColorToBuffer(this.view, id, r, g, b)
     }
     if (config?.material) {
-      const { ambient, diffuse, specular, shininess } = config.material
-      writeSphereMaterialToBuffer(this.view, id, ambient, diffuse, specular, shininess)
+      this.setSphereMaterial(id, config.material)
     }
 
     return id
@@ -73,10 +114,7 @@ export class Scene {
     writeSphereColorToBuffer(this.view, id, r, g, b)
   }
 
-  setSphereMaterial(
-    id: number,
-    material: { ambient: number; diffuse: number; specular: number; shininess: number }
-  ) {
+  setSphereMaterial(id: number, material: MaterialConfig) {
     writeSphereMaterialToBuffer(
       this.view,
       id,
@@ -85,9 +123,44 @@ export class Scene {
       material.specular,
       material.shininess
     )
+    const [r, g, b] = material.emission ?? [0, 0, 0]
+    writeSphereEmissionToBuffer(this.view, id, r, g, b)
+    writeSphereShadingToBuffer(
+      this.view,
+      id,
+      material.shadingModel ?? ShadingModel.Phong,
+      material.roughness ?? 0.5,
+      material.metalness ?? 0
+    )
+  }
+
+  /**
+   * Finds the sphere under pixel (`x`, `y`) of a `width`×`height` render without rendering
+   * it. The pick's `objectId` is the ID `addSphere` returned for the sphere. Returns
+   * `undefined` if the pixel shows no sphere.
+   */
+  pick(params: { x: number; y: number; width: number; height: number; fov: number }) {
+    const { x, y, width, height, fov } = params
+    return pickFromBuffer(this.view, width, height, fov, x, y)
   }
 
   getBuffer(): SharedArrayBuffer {
     return this.buffer
   }
+
+  /**
+   * Describes the scene as versioned JSON that `Scene.fromJSON` can load again. Also
+   * makes `JSON.stringify(scene)` produce the same description.
+   */
+  toJSON(): object {
+    return JSON.parse(sceneBufferToJSON(this.view))
+  }
+
+  /** Builds a scene from JSON written by `toJSON`. Throws if the JSON is invalid. */
+  static fromJSON(json: string | object): Scene {
+    const scene = new Scene()
+    const text = typeof json === 'string' ? json : JSON.stringify(json)
+    sceneBufferFromJSON(text, scene.view)
+    return scene
+  }
 }
@@ -62,6 +62,10 @@ impl Sphere {
         }
     }
 
+    pub fn transform(&self) -> Matrix4 {
+        self.transform
+    }
+
     pub fn set_transform(&mut self, transform: Transform) -> Result<(), &'static str> {
         self.transform = transform.build();
         self.transform_inverse =
@@ -90,7 +94,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn two_equal_spheres_have_different_ids() {
         let s1 = Sphere::new();
         let s2 = Sphere::new();
@@ -99,7 +103,7 @@ mod tests {
         assert_ne!(s1.id, s2.id);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn same_object_checks_id() {
         let s1 = Sphere::new();
         let s2 = Sphere::new();
@@ -108,7 +112,7 @@ mod tests {
         assert_eq!(s1.same_object(s2), false);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_ray_intersects_a_sphere_at_two_points() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -120,7 +124,7 @@ mod tests {
         assert_eq!(xs[1].t, 6.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_ray_intersects_a_sphere_at_a_tangent() {
         let r = Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -132,7 +136,7 @@ mod tests {
         assert_eq!(xs[1].t, 5.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_ray_misses_a_sphere() {
         let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -142,7 +146,7 @@ mod tests {
         assert_eq!(xs.len(), 0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_ray_originates_inside_a_sphere() {
         let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -154,7 +158,7 @@ mod tests {
         assert_eq!(xs[1].t, 1.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_sphere_is_behind_a_ray() {
         let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -166,7 +170,7 @@ mod tests {
         assert_eq!(xs[1].t, -4.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn intersect_sets_the_object_on_the_intersection() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let s = Sphere::new();
@@ -178,14 +182,14 @@ mod tests {
         assert_eq!(xs[1].t, 6.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_spheres_default_transformation() {
         let s = Sphere::new();
 
         assert_eq!(s.transform, Matrix4::identity());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn changing_a_spheres_transformation() {
         let mut s = Sphere::new();
         let t = Matrix4::translation(2.0, 3.0, 4.0);
@@ -196,7 +200,7 @@ mod tests {
         assert_eq!(s.transform, t);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn intersecting_a_scaled_sphere_with_a_ray() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let mut s = Sphere::new();
@@ -210,7 +214,7 @@ mod tests {
         assert_eq!(xs[1].t, 7.0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn intersecting_a_translated_sphere_with_a_ray() {
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
         let mut s = Sphere::new();
@@ -222,28 +226,28 @@ mod tests {
         assert_eq!(xs.len(), 0);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn normal_on_a_sphere_at_a_point_on_the_x_axis() {
         let s = Sphere::new();
         let n = s.normal_at(Tuple::point(1.0, 0.0, 0.0));
         assert_eq!(n, Tuple::vector(1.0, 0.0, 0.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn normal_on_a_sphere_at_a_point_on_the_y_axis() {
         let s = Sphere::new();
         let n = s.normal_at(Tuple::point(0.0, 1.0, 0.0));
         assert_eq!(n, Tuple::vector(0.0, 1.0, 0.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn normal_on_a_sphere_at_a_point_on_the_z_axis() {
         let s = Sphere::new();
         let n = s.normal_at(Tuple::point(0.0, 0.0, 1.0));
         assert_eq!(n, Tuple::vector(0.0, 0.0, 1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn normal_on_a_sphere_at_a_nonaxial_point() {
         let s = Sphere::new();
         let n = s.normal_at(Tuple::point(
@@ -257,7 +261,7 @@ mod tests {
         );
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_normal_is_a_normalized_vector() {
         let s = Sphere::new();
         let n = s.normal_at(Tuple::point(
@@ -268,7 +272,7 @@ mod tests {
         assert_eq!(n, n.normalize());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn computing_the_normal_on_a_translated_sphere() {
         let mut s = Sphere::new();
         s.set_transform(Transform::new().translate(0.0, 1.0, 0.0))
@@ -277,7 +281,7 @@ mod tests {
         assert_eq!(n, Tuple::vector(0.0, 0.70711, -0.70711));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn computing_the_normal_on_a_transformed_sphere() {
         let mut s = Sphere::new();
         s.set_transform(
@@ -290,14 +294,14 @@ mod tests {
         assert_eq!(n, Tuple::vector(0.0, 0.97014, -0.24254));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn sphere_has_a_default_material() {
         let s = Sphere::new();
         let m = Material::default();
         assert_eq!(s.material, m);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn sphere_may_be_assigned_a_material() {
         let mut s = Sphere::new();
         let mut m = Material::default();
@@ -0,0 +1,356 @@
+//! Splitting one render across several threads.
+//!
+//! A `SharedFrame` owns the camera, world and pixels of a single frame. Any number of
+//! threads may call `render_rows` on it at once: each repeatedly claims the next
+//! unrendered row from an atomic counter and writes its pixels straight into the
+//! frame, so faster threads simply take more rows and no thread waits for another
+//! until the frame runs out of work.
+//!
+//! On the web this backs the `threads` build, where wasm workers share one linear
+//! memory and find the same frame through its handle.
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use crate::camera::Camera;
+use crate::canvas::Canvas;
+use crate::tuple::Tuple;
+use crate::world::World;
+
+#[cfg(all(
+    feature = "threads",
+    target_arch = "wasm32",
+    not(target_feature = "atomics")
+))]
+compile_error!("the `threads` feature requires building with `-C target-feature=+atomics`");
+
+/// One frame being rendered cooperatively by any number of threads.
+pub struct SharedFrame {
+    camera: Camera,
+    world: World,
+    /// Every lane of every pixel in row-major order, as `f32` bits. Each row is written
+    /// only by the thread that claimed it.
+    pixels: Vec<AtomicU32>,
+    next_row: AtomicUsize,
+    finished_rows: AtomicUsize,
+}
+
+impl SharedFrame {
+    pub fn new(camera: Camera, world: World) -> SharedFrame {
+        let (width, height) = camera.size();
+        SharedFrame {
+            camera,
+            world,
+            pixels: (0..width * height * 4).map(|_| AtomicU32::new(0)).collect(),
+            next_row: AtomicUsize::new(0),
+            finished_rows: AtomicUsize::new(0),
+        }
+    }
+
+    /// Renders rows until none are left to claim, returning how many this call traced.
+    pub fn render_rows(&self) -> usize {
+        let (width, height) = self.camera.size();
+        let mut rendered = 0;
+
+        loop {
+            let y = self.next_row.fetch_add(1, Ordering::Relaxed);
+            if y >= height {
+                return rendered;
+            }
+
+            let row = &self.pixels[y * width * 4..(y + 1) * width * 4];
+            for (x, pixel) in row.as_chunks::<4>().0.iter().enumerate() {
+                let color = self.world.color_at(self.camera.ray_for_pixel(x, y));
+                for (lane, value) in pixel.iter().zip(color.as_array()) {
+                    lane.store(value.to_bits(), Ordering::Relaxed);
+                }
+            }
+            self.finished_rows.fetch_add(1, Ordering::Release);
+            rendered += 1;
+        }
+    }
+
+    /// True once every row has been traced.
+    pub fn is_complete(&self) -> bool {
+        self.finished_rows.load(Ordering::Acquire) == self.camera.size().1
+    }
+
+    /// The rendered image. Rows that have not finished yet are black, or partly drawn
+    /// if a thread is still tracing them.
+    pub fn canvas(&self) -> Canvas {
+        let (width, height) = self.camera.size();
+        let mut canvas = Canvas::new(width, height);
+        // Pairs with the release when a row finishes, so finished rows are read whole
+        self.finished_rows.load(Ordering::Acquire);
+        let lane = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+        let (lanes, _) = self.pixels.as_chunks::<4>();
+        for (pixel, [x, y, z, w]) in canvas.pixels_mut().iter_mut().zip(lanes) {
+            *pixel = Tuple::new(lane(x), lane(y), lane(z), lane(w));
+        }
+        canvas
+    }
+}
+
+#[cfg(feature = "threads")]
+mod web {
+    use std::ptr;
+    use std::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use wasm_bindgen::prelude::*;
+    use web_sys::ImageData;
+
+    use super::SharedFrame;
+    use crate::scene::parse_scene_buffer;
+    use crate::tonemap::ToneMapping;
+
+    /// How many `SharedRender`s may be alive at once.
+    const SLOT_COUNT: u32 = 64;
+
+    /// Where a live `SharedRender` publishes its frame to workers. Statics live in the
+    /// shared wasm memory, so every worker sees the same slots.
+    ///
+    /// The browser main thread may not block, so nothing here takes a lock: frames are
+    /// published and withdrawn with atomic swaps, and `readers` keeps a withdrawn frame
+    /// alive while a worker is still taking its own reference to it.
+    struct Slot {
+        /// The frame's `Arc`, turned into a raw pointer, or null if the slot is free.
+        frame: AtomicPtr<SharedFrame>,
+        handle: AtomicU32,
+        readers: AtomicU32,
+    }
+
+    static SLOTS: [Slot; SLOT_COUNT as usize] = [const {
+        Slot {
+            frame: AtomicPtr::new(ptr::null_mut()),
+            handle: AtomicU32::new(0),
+            readers: AtomicU32::new(0),
+        }
+    }; SLOT_COUNT as usize];
+    static NEXT_SERIAL: AtomicU32 = AtomicU32::new(1);
+
+    /// Publishes `frame` in a free slot and returns its handle, or `None` if every slot
+    /// is taken.
+    fn register(frame: &Arc<SharedFrame>) -> Option<u32> {
+        let raw = Arc::into_raw(Arc::clone(frame)).cast_mut();
+        for (index, slot) in (0..SLOT_COUNT).zip(&SLOTS) {
+            if slot
+                .frame
+                .compare_exchange(ptr::null_mut(), raw, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                // The slot index sits in the low bits, and the serial keeps a handle
+                // from matching a later frame in the same slot
+                let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+                let handle = serial.wrapping_mul(SLOT_COUNT).wrapping_add(index);
+                slot.handle.store(handle, Ordering::SeqCst);
+                return Some(handle);
+            }
+        }
+        // SAFETY: `raw` came from `Arc::into_raw` above and was never published
+        drop(unsafe { Arc::from_raw(raw) });
+        None
+    }
+
+    /// Withdraws the frame published under `handle` and releases the slot's reference
+    /// once no worker is in the middle of looking it up.
+    fn unregister(handle: u32) {
+        let slot = &SLOTS[(handle % SLOT_COUNT) as usize];
+        slot.handle.store(0, Ordering::SeqCst);
+        let raw = slot.frame.swap(ptr::null_mut(), Ordering::SeqCst);
+        // Lookups hold `readers` for a few instructions only, so this spins briefly
+        while slot.readers.load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        if !raw.is_null() {
+            // SAFETY: `raw` came from `Arc::into_raw` in `register`, and the swap
+            // above took it out of the slot, so it is released exactly once
+            drop(unsafe { Arc::from_raw(raw) });
+        }
+    }
+
+    /// The frame published under `handle`, if its `SharedRender` is still alive.
+    pub(super) fn lookup(handle: u32) -> Option<Arc<SharedFrame>> {
+        let slot = &SLOTS[(handle % SLOT_COUNT) as usize];
+        slot.readers.fetch_add(1, Ordering::SeqCst);
+        let raw = slot.frame.load(Ordering::SeqCst);
+        let frame =
+            (!raw.is_null() && slot.handle.load(Ordering::SeqCst) == handle).then(|| {
+                // SAFETY: the frame was still published after `readers` went up, so
+                // `unregister` keeps the slot's reference until this one is taken
+                unsafe {
+                    Arc::increment_strong_count(raw);
+                    Arc::from_raw(raw)
+                }
+            });
+        slot.readers.fetch_sub(1, Ordering::SeqCst);
+        frame
+    }
+
+    /// A frame that several web workers render together over shared wasm memory.
+    ///
+    /// Create it on the main thread, pass `handle` to every worker’s
+    /// `renderSharedRows`, and read the result with `toImageData` once all workers
+    /// have returned.
+    #[wasm_bindgen]
+    pub struct SharedRender {
+        frame: Arc<SharedFrame>,
+        handle: u32,
+        tone_mapping: ToneMapping,
+    }
+
+    #[wasm_bindgen]
+    impl SharedRender {
+        /// Prepares a frame of the scene in `buffer`. Throws if the buffer is invalid
+        /// or 64 shared renders are already alive.
+        #[wasm_bindgen(constructor)]
+        pub fn new(
+            buffer: &[u8],
+            width: u32,
+            height: u32,
+            fov: f32,
+        ) -> Result<SharedRender, JsValue> {
+            let (camera, world, tone_mapping) =
+                parse_scene_buffer(buffer, width as usize, height as usize, fov)?;
+            let frame = Arc::new(SharedFrame::new(camera, world));
+            let handle = register(&frame).ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "at most {SLOT_COUNT} shared renders may be alive at once"
+                ))
+            })?;
+            Ok(SharedRender {
+                frame,
+                handle,
+                tone_mapping,
+            })
+        }
+
+        /// Identifies the frame to `renderSharedRows` until this object is freed.
+        #[wasm_bindgen(getter)]
+        pub fn handle(&self) -> u32 {
+            self.handle
+        }
+
+        #[wasm_bindgen(getter, js_name = isComplete)]
+        pub fn is_complete(&self) -> bool {
+            self.frame.is_complete()
+        }
+
+        #[wasm_bindgen(js_name = toImageData)]
+        pub fn to_image_data(&self) -> ImageData {
+            self.frame.canvas().to_image_data_with(self.tone_mapping)
+        }
+    }
+
+    impl Drop for SharedRender {
+        fn drop(&mut self) {
+            unregister(self.handle);
+        }
+    }
+
+    /// Renders rows of the shared frame with this handle until none are left, returning
+    /// how many rows this worker traced.
+    ///
+    /// Throws if no live `SharedRender` has the handle. A frame freed while workers
+    /// are rendering it stays alive until they return.
+    #[wasm_bindgen(js_name = renderSharedRows)]
+    pub fn render_shared_rows(handle: u32) -> Result<usize, JsValue> {
+        let frame = lookup(handle).ok_or_else(|| {
+            JsValue::from_str(&format!("no shared render has handle {handle}"))
+        })?;
+        Ok(frame.render_rows())
+    }
+}
+
+#[cfg(feature = "threads")]
+pub use web::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::view_transform;
+    use wasm_bindgen_test::*;
+
+    fn frame(width: usize, height: usize) -> (SharedFrame, Canvas) {
+        let mut c = Camera::new(width, height, std::f32::consts::FRAC_PI_2);
+        c.set_transform(view_transform(
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
+        let w = World::default();
+        let expected = c.render(&w);
+        (SharedFrame::new(c, w), expected)
+    }
+
+    fn assert_same_image(a: &Canvas, b: &Canvas) {
+        assert_eq!((a.width, a.height), (b.width, b.height));
+        for y in 0..a.height {
+            for x in 0..a.width {
+                assert_eq!(a.pixel_at(x, y).as_array(), b.pixel_at(x, y).as_array());
+            }
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn a_new_shared_frame_is_incomplete() {
+        let (f, _) = frame(5, 3);
+        assert!(!f.is_complete());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn a_single_caller_renders_every_row() {
+        let (f, expected) = frame(11, 7);
+        assert_eq!(f.render_rows(), 7);
+        assert!(f.is_complete());
+        assert_same_image(&f.canvas(), &expected);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rendering_a_finished_frame_claims_no_rows() {
+        let (f, _) = frame(4, 4);
+        f.render_rows();
+        assert_eq!(f.render_rows(), 0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn threads_share_rows_and_match_the_serial_render() {
+        let (f, expected) = frame(23, 31);
+        let counts: Vec<usize> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4).map(|_| s.spawn(|| f.render_rows())).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(counts.iter().sum::<usize>(), 31);
+        assert!(f.is_complete());
+        assert_same_image(&f.canvas(), &expected);
+    }
+
+    #[cfg(feature = "threads")]
+    #[wasm_bindgen_test(unsupported = test)]
+    fn shared_renders_are_found_by_handle_until_freed() {
+        use crate::scene::{
+            add_sphere_to_buffer, get_scene_buffer_size, initialize_scene_buffer,
+            write_camera_to_buffer,
+        };
+
+        let mut buffer = vec![0u8; get_scene_buffer_size(1) as usize];
+        initialize_scene_buffer(&mut buffer);
+        write_camera_to_buffer(&mut buffer, 0.0, 0.0, -5.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        add_sphere_to_buffer(&mut buffer).unwrap();
+
+        let render = SharedRender::new(&buffer, 4, 3, std::f32::consts::FRAC_PI_2).unwrap();
+        let handle = render.handle();
+        assert_eq!(render_shared_rows(handle).ok(), Some(3));
+        assert!(render.is_complete());
+
+        drop(render);
+        assert!(web::lookup(handle).is_none());
+
+        // A later render may reuse the slot, but never answers to the old handle
+        let later = SharedRender::new(&buffer, 4, 3, std::f32::consts::FRAC_PI_2).unwrap();
+        assert_ne!(later.handle(), handle);
+        assert!(web::lookup(handle).is_none());
+        assert!(web::lookup(later.handle()).is_some());
+    }
+}
@@ -0,0 +1,147 @@
+//! Converting linear scene colors to display values.
+//!
+//! Renders produce unbounded linear light. Displaying them takes three steps: scale by
+//! the exposure, compress the range into [0, 1] with a tone mapper, and optionally
+//! encode the result with the sRGB transfer function so midtones are not too dark.
+//! The default does none of the compression or encoding, matching the original
+//! clamp-and-round output.
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::tuple::Tuple;
+
+/// How colors outside [0, 1] are brought into the displayable range.
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum ToneMapper {
+    /// Clip each channel to [0, 1]. Bright highlights saturate to flat white.
+    #[default]
+    Clamp = 0,
+    /// `x / (1 + x)` per channel: gentle compression that never quite reaches white.
+    Reinhard = 1,
+    /// Narkowicz’s fit of the ACES filmic curve: a slight toe and a soft shoulder.
+    Aces = 2,
+}
+
+/// Exposure, tone mapping and output encoding applied when converting to bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ToneMapping {
+    pub mapper: ToneMapper,
+    /// Exposure adjustment in stops; each stop doubles the brightness.
+    pub exposure: f32,
+    /// Encode with the sRGB transfer function instead of writing linear values.
+    pub srgb: bool,
+}
+
+impl ToneMapping {
+    pub fn new(mapper: ToneMapper, exposure: f32, srgb: bool) -> ToneMapping {
+        ToneMapping {
+            mapper,
+            exposure,
+            srgb,
+        }
+    }
+
+    /// Maps one linear channel value to a display value in [0, 1].
+    pub fn map_channel(&self, value: f32) -> f32 {
+        let exposed = value * self.exposure.exp2();
+        let mapped = match self.mapper {
+            ToneMapper::Clamp => exposed,
+            ToneMapper::Reinhard => exposed.max(0.0) / (1.0 + exposed.max(0.0)),
+            ToneMapper::Aces => aces(exposed),
+        }
+        .clamp(0.0, 1.0);
+        if self.srgb {
+            srgb_encode(mapped)
+        } else {
+            mapped
+        }
+    }
+
+    /// Maps a linear color to 8-bit red, green and blue values.
+    pub fn to_rgb8(&self, color: Tuple) -> [u8; 3] {
+        [color.x(), color.y(), color.z()]
+            .map(|v| (self.map_channel(v) * 255.0).round() as u8)
+    }
+}
+
+fn aces(x: f32) -> f32 {
+    const A: f32 = 2.51;
+    const B: f32 = 0.03;
+    const C: f32 = 2.43;
+    const D: f32 = 0.59;
+    const E: f32 = 0.14;
+    let x = x.max(0.0);
+    (x * (A * x + B)) / (x * (C * x + D) + E)
+}
+
+/// Applies the sRGB transfer function to a linear value in [0, 1].
+pub fn srgb_encode(linear: f32) -> f32 {
+    if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy::fuzzy_eq_f32;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn the_default_matches_scaling_and_rounding() {
+        let t = ToneMapping::default();
+        for v in [-0.5, 0.0, 0.1, 0.5, 0.73, 1.0, 3.0] {
+            let expected = (v * 255.0_f32).round() as u8;
+            assert_eq!(t.to_rgb8(Tuple::color(v, v, v)), [expected; 3]);
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn each_stop_of_exposure_doubles_brightness() {
+        let t = ToneMapping::new(ToneMapper::Clamp, 1.0, false);
+        assert!(fuzzy_eq_f32(t.map_channel(0.2), 0.4));
+        let t = ToneMapping::new(ToneMapper::Clamp, -2.0, false);
+        assert!(fuzzy_eq_f32(t.map_channel(0.8), 0.2));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn reinhard_compresses_highlights_without_clipping() {
+        let t = ToneMapping::new(ToneMapper::Reinhard, 0.0, false);
+        assert!(fuzzy_eq_f32(t.map_channel(1.0), 0.5));
+        assert!(t.map_channel(4.0) < t.map_channel(8.0));
+        assert!(t.map_channel(8.0) < 1.0);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn aces_maps_black_to_black_and_saturates_bright_values() {
+        let t = ToneMapping::new(ToneMapper::Aces, 0.0, false);
+        assert_eq!(t.map_channel(0.0), 0.0);
+        assert!(fuzzy_eq_f32(t.map_channel(0.18), 0.2669));
+        assert_eq!(t.map_channel(100.0), 1.0);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn srgb_encoding_brightens_midtones() {
+        assert_eq!(srgb_encode(0.0), 0.0);
+        assert!(fuzzy_eq_f32(srgb_encode(1.0), 1.0));
+        assert!(fuzzy_eq_f32(srgb_encode(0.002), 0.02584));
+        // Linear 18% grey is about 46% in sRGB
+        assert!(fuzzy_eq_f32(srgb_encode(0.18), 0.46135));
+        let t = ToneMapping::new(ToneMapper::Clamp, 0.0, true);
+        assert_eq!(t.to_rgb8(Tuple::color(0.18, 0.0, 1.0)), [118, 0, 255]);
+    }
+}
@@ -1,9 +1,10 @@
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 
 use crate::matrix::Matrix4;
 use crate::tuple::Tuple;
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)]
 #[derive(Clone, Debug)]
 pub struct Transform {
     operations: Vec<Matrix4>,
@@ -15,9 +16,9 @@ impl Default for Transform {
     }
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)]
 impl Transform {
-    #[wasm_bindgen(constructor)]
+    #[cfg_attr(feature = "web", wasm_bindgen(constructor))]
     pub fn new() -> Transform {
         Transform {
             operations: Vec::new(),
@@ -36,21 +37,21 @@ impl Transform {
         Transform { operations }
     }
 
-    #[wasm_bindgen(js_name = rotateX)]
+    #[cfg_attr(feature = "web", wasm_bindgen(js_name = rotateX))]
     pub fn rotate_x(self, r: f32) -> Transform {
         let mut operations = self.operations;
         operations.push(Matrix4::rotation_x(r));
         Transform { operations }
     }
 
-    #[wasm_bindgen(js_name = rotateY)]
+    #[cfg_attr(feature = "web", wasm_bindgen(js_name = rotateY))]
     pub fn rotate_y(self, r: f32) -> Transform {
         let mut operations = self.operations;
         operations.push(Matrix4::rotation_y(r));
         Transform { operations }
     }
 
-    #[wasm_bindgen(js_name = rotateZ)]
+    #[cfg_attr(feature = "web", wasm_bindgen(js_name = rotateZ))]
     pub fn rotate_z(self, r: f32) -> Transform {
         let mut operations = self.operations;
         operations.push(Matrix4::rotation_z(r));
@@ -106,7 +107,7 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_transformation_matrix_for_the_default_orientation() {
         let from = Tuple::point(0.0, 0.0, 0.0);
         let to = Tuple::point(0.0, 0.0, -1.0);
@@ -115,7 +116,7 @@ mod tests {
         assert_eq!(t, Matrix4::identity());
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn a_view_transformation_matrix_looking_in_positive_z_direction() {
         let from = Tuple::point(0.0, 0.0, 0.0);
         let to = Tuple::point(0.0, 0.0, 1.0);
@@ -124,7 +125,7 @@ mod tests {
         assert_eq!(t, Matrix4::scaling(-1.0, 1.0, -1.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn the_view_transformation_moves_the_world() {
         let from = Tuple::point(0.0, 0.0, 8.0);
         let to = Tuple::point(0.0, 0.0, 0.0);
@@ -133,7 +134,7 @@ mod tests {
         assert_eq!(t, Matrix4::translation(0.0, 0.0, -8.0));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn an_arbitrary_view_transformation() {
         let from = Tuple::point(1.0, 3.0, 2.0);
         let to = Tuple::point(4.0, -2.0, 8.0);
@@ -146,7 +147,7 @@ mod tests {
         assert_eq!(t, expected);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     pub fn chained_transformations_must_be_applied_in_reverse_order() {
         let p = Tuple::point(1.0, 0.0, 1.0);
         let t = Transform::new()
@@ -22,8 +22,11 @@ impl World {
 
     pub fn intersect(&self, ray: Ray) -> IntersectionCollection<'_> {
         let mut intersections: Vec<Intersection> = Vec::new();
-        for object in &self.objects {
+        for (index, object) in self.objects.iter().enumerate() {
             let mut object_intersections = object.intersect(&ray).xs;
+            for intersection in &mut object_intersections {
+                intersection.object_index = Some(index);
+            }
             intersections.append(&mut object_intersections);
         }
         IntersectionCollection::new(intersections)
@@ -107,14 +110,14 @@ mod tests {
     use super::*;
     use wasm_bindgen_test::*;
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn creating_a_world() {
         let w = World::new();
         assert_eq!(w.objects.len(), 0);
         assert_eq!(w.light.is_none(), true);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn default_world() {
         let light = Light::new(
             Tuple::point(-10.0, 10.0, -10.0),
@@ -135,7 +138,7 @@ mod tests {
         assert!(w.iter().any(|obj| obj == &s2));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn intersect_world_with_ray() {
         let w = World::default();
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -145,9 +148,14 @@ mod tests {
         assert_eq!(intersections[1].t, 4.5);
         assert_eq!(intersections[2].t, 5.5);
         assert_eq!(intersections[3].t, 6.0);
+
+        // Each hit records which of the world's objects it is on
+        let indices: Vec<_> = (0..4).map(|i| intersections[i].object_index).collect();
+        assert_eq!(indices, [Some(0), Some(1), Some(1), Some(0)]);
+        assert_eq!(w.objects[0].intersect(&r)[0].object_index, None);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn shading_an_intersection() {
         let w = World::default();
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -160,7 +168,7 @@ mod tests {
         assert!(c.rgb_eq(Tuple::color(0.38066, 0.47583, 0.2855)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn shading_an_intersection_from_the_inside() {
         let mut w = World::default();
         w.light = Some(Light::new(
@@ -176,7 +184,7 @@ mod tests {
         assert!(c.rgb_eq(Tuple::color(0.90498, 0.90498, 0.90498)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_color_when_a_ray_misses() {
         let w = World::default();
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
@@ -185,7 +193,7 @@ mod tests {
         assert!(c.rgb_eq(black));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_color_when_a_ray_hits() {
         let w = World::default();
         let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -193,7 +201,7 @@ mod tests {
         assert!(c.rgb_eq(Tuple::color(0.38066, 0.47583, 0.2855)));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_color_with_an_intersection_behind_the_ray() {
         let mut w = World::default();
 
@@ -211,35 +219,35 @@ mod tests {
         assert!(c.rgb_eq(inner_material_color));
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn there_is_no_shadow_when_nothing_is_collinear_with_point_and_light() {
         let w = World::default();
         let p = Tuple::point(0.0, 10.0, 0.0);
         assert_eq!(w.is_shadowed(p), false);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn the_shadow_when_an_object_is_between_the_point_and_the_light() {
         let w = World::default();
         let p = Tuple::point(10.0, -10.0, 10.0);
         assert_eq!(w.is_shadowed(p), true);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn there_is_no_shadow_when_an_object_is_behind_the_light() {
         let w = World::default();
         let p = Tuple::point(-20.0, 20.0, -20.0);
         assert_eq!(w.is_shadowed(p), false);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn there_is_no_shadow_when_an_object_is_behind_the_point() {
         let w = World::default();
         let p = Tuple::point(-2.0, 2.0, -2.0);
         assert_eq!(w.is_shadowed(p), false);
     }
 
-    #[wasm_bindgen_test]
+    #[wasm_bindgen_test(unsupported = test)]
     fn shade_hit_is_given_an_intersection_in_shadow() {
         let mut w = World::new();
         w.light = Some(Light::new(
@@ -0,0 +1,583 @@
+//! Scene descriptions in the Ray Tracer Challenge’s YAML format.
+//!
+//! A scene file is a list of entries. `add: camera` and `add: light` set up the view and
+//! the point light, `add: sphere` places an object, and `define: <name>` names a
+//! material or transform list for later entries to reuse, optionally `extend`ing an
+//! earlier material definition:
+//!
+//! ```yaml
+//! - add: camera
+//!   width: 100
+//!   height: 50
+//!   field-of-view: 1.047
+//!   from: [0, 1.5, -5]
+//!   to: [0, 1, 0]
+//!   up: [0, 1, 0]
+//! - add: light
+//!   at: [-10, 10, -10]
+//!   intensity: [1, 1, 1]
+//! - define: red
+//!   value:
+//!     color: [1, 0.2, 0.1]
+//!     specular: 0.3
+//! - add: sphere
+//!   material: red
+//!   transform:
+//!     - [scale, 0.5, 0.5, 0.5]
+//!     - [translate, 0, 0.5, 0]
+//! ```
+//!
+//! Transforms apply in the order listed. Spheres are the only shape, and material
+//! properties the renderer has no use for, such as `reflective` or `pattern`, are
+//! ignored so that scenes written for other renderers still load.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::camera::Camera;
+use crate::light::Light;
+use crate::material::Material;
+use crate::matrix::Matrix4;
+use crate::sphere::Sphere;
+use crate::transform::{view_transform, Transform};
+use crate::tuple::Tuple;
+use crate::world::World;
+
+/// Why a YAML scene could not be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum YamlError {
+    /// The text is not well-formed YAML.
+    Syntax(String),
+    /// The document is not a list of `add` and `define` entries.
+    NotAList,
+    /// An entry has neither an `add` nor a `define` key.
+    UnknownEntry(usize),
+    /// An `add` entry names an object this renderer cannot draw.
+    UnsupportedObject(String),
+    /// An entry lacks a key it needs.
+    MissingKey { entry: String, key: &'static str },
+    /// A value has the wrong type or number of components.
+    InvalidValue { key: String, expected: &'static str },
+    /// A material or transform refers to a name that has not been defined yet.
+    Undefined(String),
+    /// A transform list holds an operation other than translate, scale, rotate or shear.
+    UnknownTransform(String),
+    /// An object’s transform cannot be inverted.
+    SingularTransform,
+    /// The scene has no camera or no light.
+    Missing(&'static str),
+    /// The scene adds more than one camera or light.
+    Duplicate(&'static str),
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::Syntax(message) => write!(f, "invalid YAML: {message}"),
+            YamlError::NotAList => write!(f, "a scene must be a list of entries"),
+            YamlError::UnknownEntry(index) => {
+                write!(f, "entry {index} has neither an add nor a define key")
+            }
+            YamlError::UnsupportedObject(kind) => {
+                write!(f, "cannot add {kind:?}; only spheres are supported")
+            }
+            YamlError::MissingKey { entry, key } => write!(f, "{entry} is missing {key}"),
+            YamlError::InvalidValue { key, expected } => {
+                write!(f, "{key} must be {expected}")
+            }
+            YamlError::Undefined(name) => write!(f, "{name:?} has not been defined"),
+            YamlError::UnknownTransform(op) => write!(f, "unknown transform {op:?}"),
+            YamlError::SingularTransform => write!(f, "object transform is not invertible"),
+            YamlError::Missing(what) => write!(f, "scene has no {what}"),
+            YamlError::Duplicate(what) => write!(f, "scene adds more than one {what}"),
+        }
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// A value named by a `define` entry.
+enum Definition {
+    /// Material properties, already merged with any definition they extend.
+    Material(yaml_rust2::yaml::Hash),
+    /// A transform list, composed into one matrix.
+    Transform(Matrix4),
+}
+
+/// Builds the camera and world described by a YAML scene.
+pub fn parse_yaml_scene(source: &str) -> Result<(Camera, World), YamlError> {
+    let documents =
+        YamlLoader::load_from_str(source).map_err(|e| YamlError::Syntax(e.to_string()))?;
+    let entries = documents
+        .first()
+        .and_then(Yaml::as_vec)
+        .ok_or(YamlError::NotAList)?;
+
+    let mut definitions = HashMap::new();
+    let mut camera = None;
+    let mut world = World::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        if let Some(name) = entry["define"].as_str() {
+            let definition = define(entry, name, &definitions)?;
+            definitions.insert(name.to_string(), definition);
+            continue;
+        }
+        match entry["add"].as_str() {
+            Some("camera") if camera.is_some() => {
+                return Err(YamlError::Duplicate("camera"))
+            }
+            Some("camera") => camera = Some(parse_camera(entry)?),
+            Some("light") if world.light.is_some() => {
+                return Err(YamlError::Duplicate("light"))
+            }
+            Some("light") => world.light = Some(parse_light(entry)?),
+            Some("sphere") => world.objects.push(parse_sphere(entry, &definitions)?),
+            Some(kind) => return Err(YamlError::UnsupportedObject(kind.to_string())),
+            None => return Err(YamlError::UnknownEntry(index)),
+        }
+    }
+
+    let camera = camera.ok_or(YamlError::Missing("camera"))?;
+    if world.light.is_none() {
+        return Err(YamlError::Missing("light"));
+    }
+    Ok((camera, world))
+}
+
+fn define(
+    entry: &Yaml,
+    name: &str,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Definition, YamlError> {
+    match &entry["value"] {
+        Yaml::Hash(properties) => {
+            let mut merged = match entry["extend"].as_str() {
+                Some(base) => match definitions.get(base) {
+                    Some(Definition::Material(base)) => base.clone(),
+                    Some(Definition::Transform(_)) => {
+                        return Err(YamlError::InvalidValue {
+                            key: format!("{name}.extend"),
+                            expected: "the name of a material",
+                        })
+                    }
+                    None => return Err(YamlError::Undefined(base.to_string())),
+                },
+                None => yaml_rust2::yaml::Hash::new(),
+            };
+            for (key, value) in properties {
+                merged.insert(key.clone(), value.clone());
+            }
+            Ok(Definition::Material(merged))
+        }
+        Yaml::Array(_) => Ok(Definition::Transform(transform_matrix(
+            &entry["value"],
+            definitions,
+        )?)),
+        Yaml::BadValue => Err(YamlError::MissingKey {
+            entry: format!("define {name}"),
+            key: "value",
+        }),
+        _ => Err(YamlError::InvalidValue {
+            key: format!("{name}.value"),
+            expected: "a material or a transform list",
+        }),
+    }
+}
+
+fn parse_camera(entry: &Yaml) -> Result<Camera, YamlError> {
+    let size = |key| {
+        let value = required(entry, "camera", key)?;
+        value
+            .as_i64()
+            .and_then(|v| usize::try_from(v).ok())
+            .filter(|&v| v > 0)
+            .ok_or_else(|| YamlError::InvalidValue {
+                key: key.to_string(),
+                expected: "a positive integer",
+            })
+    };
+    let (width, height) = (size("width")?, size("height")?);
+    let field_of_view =
+        number(required(entry, "camera", "field-of-view")?, "field-of-view")?;
+    let [fx, fy, fz] = triple(required(entry, "camera", "from")?, "from")?;
+    let [tx, ty, tz] = triple(required(entry, "camera", "to")?, "to")?;
+    let [ux, uy, uz] = triple(required(entry, "camera", "up")?, "up")?;
+
+    let mut camera = Camera::new(width, height, field_of_view);
+    camera
+        .set_transform(view_transform(
+            Tuple::point(fx, fy, fz),
+            Tuple::point(tx, ty, tz),
+            Tuple::vector(ux, uy, uz),
+        ))
+        .map_err(|_| YamlError::InvalidValue {
+            key: "up".to_string(),
+            expected: "a vector that is not parallel to the view direction",
+        })?;
+    Ok(camera)
+}
+
+fn parse_light(entry: &Yaml) -> Result<Light, YamlError> {
+    let [x, y, z] = triple(required(entry, "light", "at")?, "at")?;
+    let [r, g, b] = triple(required(entry, "light", "intensity")?, "intensity")?;
+    Ok(Light::new(Tuple::point(x, y, z), Tuple::color(r, g, b)))
+}
+
+fn parse_sphere(
+    entry: &Yaml,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Sphere, YamlError> {
+    let mut sphere = Sphere::new();
+    match &entry["material"] {
+        Yaml::BadValue => {}
+        Yaml::String(name) => match definitions.get(name) {
+            Some(Definition::Material(properties)) => {
+                sphere.material = parse_material(properties)?
+            }
+            Some(Definition::Transform(_)) => {
+                return Err(YamlError::InvalidValue {
+                    key: "material".to_string(),
+                    expected: "a map of properties or the name of a material",
+                })
+            }
+            None => return Err(YamlError::Undefined(name.clone())),
+        },
+        Yaml::Hash(properties) => sphere.material = parse_material(properties)?,
+        _ => {
+            return Err(YamlError::InvalidValue {
+                key: "material".to_string(),
+                expected: "a map of properties or the name of a material",
+            })
+        }
+    }
+    if !entry["transform"].is_badvalue() {
+        let matrix = transform_matrix(&entry["transform"], definitions)?;
+        sphere
+            .set_transform(Transform::from_matrix(matrix))
+            .map_err(|_| YamlError::SingularTransform)?;
+    }
+    Ok(sphere)
+}
+
+fn parse_material(properties: &yaml_rust2::yaml::Hash) -> Result<Material, YamlError> {
+    let mut material = Material::default();
+    for (key, value) in properties {
+        let Some(key) = key.as_str() else { continue };
+        match key {
+            "color" => {
+                let [r, g, b] = triple(value, key)?;
+                material.color = Tuple::color(r, g, b);
+            }
+            "ambient" => material.ambient = number(value, key)?,
+            "diffuse" => material.diffuse = number(value, key)?,
+            "specular" => material.specular = number(value, key)?,
+            "shininess" => material.shininess = number(value, key)?,
+            _ => {}
+        }
+    }
+    Ok(material)
+}
+
+/// Composes a transform list into one matrix. Each item is either an operation such as
+/// `[translate, 1, 2, 3]` or the name of a defined transform list.
+fn transform_matrix(
+    list: &Yaml,
+    definitions: &HashMap<String, Definition>,
+) -> Result<Matrix4, YamlError> {
+    let items = list.as_vec().ok_or_else(|| YamlError::InvalidValue {
+        key: "transform".to_string(),
+        expected: "a list",
+    })?;
+    let mut matrix = Matrix4::identity();
+    for item in items {
+        let step = match item {
+            Yaml::String(name) => match definitions.get(name) {
+                Some(Definition::Transform(defined)) => *defined,
+                Some(Definition::Material(_)) => {
+                    return Err(YamlError::InvalidValue {
+                        key: "transform".to_string(),
+                        expected: "a list of operations and transform names",
+                    })
+                }
+                None => return Err(YamlError::Undefined(name.clone())),
+            },
+            Yaml::Array(operation) => transform_operation(operation)?,
+            _ => {
+                return Err(YamlError::InvalidValue {
+                    key: "transform".to_string(),
+                    expected: "a list of operations and transform names",
+                })
+            }
+        };
+        matrix = step * matrix;
+    }
+    Ok(matrix)
+}
+
+fn transform_operation(operation: &[Yaml]) -> Result<Matrix4, YamlError> {
+    let Some((name, args)) = operation.split_first() else {
+        return Err(YamlError::UnknownTransform(String::new()));
+    };
+    let name = name.as_str().unwrap_or_default();
+    let args = args
+        .iter()
+        .map(|arg| number(arg, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let arity = |n, expected| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(YamlError::InvalidValue {
+                key: name.to_string(),
+                expected,
+            })
+        }
+    };
+    match name {
+        "translate" => {
+            arity(3, "three numbers")?;
+            Ok(Matrix4::translation(args[0], args[1], args[2]))
+        }
+        "scale" => {
+            arity(3, "three numbers")?;
+            Ok(Matrix4::scaling(args[0], args[1], args[2]))
+        }
+        "rotate-x" => {
+            arity(1, "one angle in radians")?;
+            Ok(Matrix4::rotation_x(args[0]))
+        }
+        "rotate-y" => {
+            arity(1, "one angle in radians")?;
+            Ok(Matrix4::rotation_y(args[0]))
+        }
+        "rotate-z" => {
+            arity(1, "one angle in radians")?;
+            Ok(Matrix4::rotation_z(args[0]))
+        }
+        "shear" => {
+            arity(6, "six numbers")?;
+            Ok(Matrix4::shearing(
+                args[0], args[1], args[2], args[3], args[4], args[5],
+            ))
+        }
+        _ => Err(YamlError::UnknownTransform(name.to_string())),
+    }
+}
+
+fn required<'a>(
+    entry: &'a Yaml,
+    kind: &str,
+    key: &'static str,
+) -> Result<&'a Yaml, YamlError> {
+    match &entry[key] {
+        Yaml::BadValue => Err(YamlError::MissingKey {
+            entry: kind.to_string(),
+            key,
+        }),
+        value => Ok(value),
+    }
+}
+
+fn number(value: &Yaml, key: &str) -> Result<f32, YamlError> {
+    match value {
+        Yaml::Integer(n) => Some(*n as f32),
+        Yaml::Real(_) => value.as_f64().map(|n| n as f32),
+        _ => None,
+    }
+    .ok_or_else(|| YamlError::InvalidValue {
+        key: key.to_string(),
+        expected: "a number",
+    })
+}
+
+fn triple(value: &Yaml, key: &str) -> Result<[f32; 3], YamlError> {
+    let invalid = || YamlError::InvalidValue {
+        key: key.to_string(),
+        expected: "a list of three numbers",
+    };
+    match value.as_vec().map(Vec::as_slice) {
+        Some([x, y, z]) => Ok([
+            number(x, key).map_err(|_| invalid())?,
+            number(y, key).map_err(|_| invalid())?,
+            number(z, key).map_err(|_| invalid())?,
+        ]),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzzy::fuzzy_eq_f32;
+    use std::f32::consts::FRAC_PI_2;
+    use wasm_bindgen_test::*;
+
+    const HEADER: &str = "
+- add: camera
+  width: 100
+  height: 50
+  field-of-view: 0.785
+  from: [ 0, 1.5, -5 ]
+  to: [ 0, 1, 0 ]
+  up: [ 0, 1, 0 ]
+- add: light
+  at: [ -10, 10, -10 ]
+  intensity: [ 1, 1, 1 ]
+";
+
+    fn scene(body: &str) -> Result<(Camera, World), YamlError> {
+        parse_yaml_scene(&format!("{HEADER}{body}"))
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn the_camera_and_light_are_read() {
+        let (camera, world) = scene("").unwrap();
+        assert_eq!(camera.size(), (100, 50));
+        assert!(fuzzy_eq_f32(camera.field_of_view(), 0.785));
+        let expected = view_transform(
+            Tuple::point(0.0, 1.5, -5.0),
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+        );
+        assert_eq!(camera.transform(), expected);
+        assert_eq!(
+            world.light,
+            Some(Light::new(
+                Tuple::point(-10.0, 10.0, -10.0),
+                Tuple::color(1.0, 1.0, 1.0)
+            ))
+        );
+        assert!(world.objects.is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn spheres_take_inline_materials_and_transforms_in_order() {
+        let (_, world) = scene(
+            "
+- add: sphere
+  material:
+    color: [ 1, 0.2, 0.1 ]
+    diffuse: 0.7
+    reflective: 0.3
+  transform:
+    - [ scale, 0.5, 0.5, 0.5 ]
+    - [ translate, 1, 2, 3 ]
+    - [ rotate-y, 1.5707963 ]
+",
+        )
+        .unwrap();
+        let sphere = &world.objects[0];
+        assert_eq!(sphere.material.color, Tuple::color(1.0, 0.2, 0.1));
+        assert_eq!(sphere.material.diffuse, 0.7);
+        assert_eq!(sphere.material.specular, Material::default().specular);
+        let expected = Matrix4::rotation_y(FRAC_PI_2)
+            * Matrix4::translation(1.0, 2.0, 3.0)
+            * Matrix4::scaling(0.5, 0.5, 0.5);
+        assert_eq!(sphere.transform(), expected);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn definitions_can_extend_materials_and_nest_transforms() {
+        let (_, world) = scene(
+            "
+- define: white-material
+  value:
+    color: [ 1, 1, 1 ]
+    diffuse: 0.7
+    ambient: 0.1
+- define: blue-material
+  extend: white-material
+  value:
+    color: [ 0.5, 0.8, 0.9 ]
+- define: standard-transform
+  value:
+    - [ translate, 1, -1, 1 ]
+    - [ scale, 0.5, 0.5, 0.5 ]
+- define: large-object
+  value:
+    - standard-transform
+    - [ scale, 3.5, 3.5, 3.5 ]
+- add: sphere
+  material: blue-material
+  transform:
+    - large-object
+    - [ translate, 8.5, 1.5, -0.5 ]
+",
+        )
+        .unwrap();
+        let sphere = &world.objects[0];
+        assert_eq!(sphere.material.color, Tuple::color(0.5, 0.8, 0.9));
+        assert_eq!(sphere.material.diffuse, 0.7);
+        assert_eq!(sphere.material.ambient, 0.1);
+        let expected = Matrix4::translation(8.5, 1.5, -0.5)
+            * Matrix4::scaling(3.5, 3.5, 3.5)
+            * Matrix4::scaling(0.5, 0.5, 0.5)
+            * Matrix4::translation(1.0, -1.0, 1.0);
+        assert_eq!(sphere.transform(), expected);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn unsupported_shapes_are_errors() {
+        let err = scene("- add: plane\n").err().unwrap();
+        assert_eq!(err, YamlError::UnsupportedObject("plane".to_string()));
+        assert!(err.to_string().contains("only spheres"));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn malformed_scenes_are_reported() {
+        assert!(matches!(
+            parse_yaml_scene("- add: [camera"),
+            Err(YamlError::Syntax(_))
+        ));
+        assert_eq!(
+            parse_yaml_scene("add: camera").err(),
+            Some(YamlError::NotAList)
+        );
+        assert_eq!(
+            scene("- add: sphere\n  material: shiny\n").err(),
+            Some(YamlError::Undefined("shiny".to_string()))
+        );
+        assert_eq!(
+            scene("- add: sphere\n  transform:\n    - [ spin, 1 ]\n").err(),
+            Some(YamlError::UnknownTransform("spin".to_string()))
+        );
+        assert!(matches!(
+            scene("- add: sphere\n  transform:\n    - [ translate, 1, 2 ]\n"),
+            Err(YamlError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            scene("- add: sphere\n  transform:\n    - [ scale, 0, 1, 1 ]\n").err(),
+            Some(YamlError::SingularTransform)
+        );
+        assert_eq!(
+            scene("- color: [ 1, 1, 1 ]\n").err(),
+            Some(YamlError::UnknownEntry(2))
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn a_scene_needs_exactly_one_camera_and_light() {
+        assert_eq!(
+            scene("- add: light\n  at: [ 0, 0, 0 ]\n  intensity: [ 1, 1, 1 ]\n").err(),
+            Some(YamlError::Duplicate("light"))
+        );
+        assert_eq!(
+            parse_yaml_scene("- add: light\n  at: [ 0, 0, 0 ]\n  intensity: [ 1, 1, 1 ]\n")
+                .err(),
+            Some(YamlError::Missing("camera"))
+        );
+        let missing = parse_yaml_scene("- add: camera\n  width: 10\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            missing,
+            YamlError::MissingKey {
+                entry: "camera".to_string(),
+                key: "height"
+            }
+        );
+    }
+}